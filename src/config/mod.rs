@@ -0,0 +1,519 @@
+use crate::editor::encoding::Encoding;
+use crossterm::style::Color;
+use log::info;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+/**
+ * 처음 화면에 보이는 줄 번호 형식
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum LineNumbers {
+    Off,
+    Absolute,
+    Relative,
+}
+
+/**
+ * 저장할 때 원래 파일을 남기는 방식
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum BackupPolicy {
+    None,
+    /// file~
+    Simple,
+    /// file.20240101-120000~
+    Timestamp,
+}
+
+/**
+ * 터미널이 표시할 수 있는 색 수
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ColorDepth {
+    Ansi16,
+    Ansi256,
+    TrueColor,
+}
+
+/**
+ * [colors] 의 한 항목. 생략한 색은 테마의 기본값을 쓴다.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ColorSpec {
+    pub fg: Option<Color>,
+    pub bg: Option<Color>,
+}
+
+/**
+사용자 설정. 값이 잘못된 항목은 기본값을 쓰고 문제 목록에 남긴다.
+
+```toml
+[editor]
+tab_width = 4
+wrap = false                # 소프트 줄바꿈. 아직 지원하지 않는다.
+line_numbers = "relative"   # "off", "absolute", "relative"
+
+[file]
+encoding = "utf-8"          # "utf-8", "utf-8-bom", "utf-16le", "utf-16be", "cp949"
+backup = "simple"           # "none", "simple", "timestamp"
+
+[display]
+theme = "classic"           # "default", "classic", "monochrome"
+color_depth = "auto"        # "auto", "16", "256", "truecolor"
+
+[colors]
+status = { fg = "black", bg = "#00aaaa" }
+
+[keys]
+preset = "wordstar"
+
+[log]
+file = "/tmp/mdedit.log"
+```
+*/
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    pub tab_width: u16,
+    /// 긴 줄을 창 너비에서 접어서 보여줄지 여부. 아직 지원하지 않아서 켜면 알리기만 한다.
+    pub wrap: bool,
+    pub line_numbers: LineNumbers,
+    /// 새 파일, ASCII 만 있는 파일의 인코딩
+    pub encoding: Encoding,
+    pub backup: BackupPolicy,
+    pub theme: String,
+    /// None 이면 환경 변수로 추측한다.
+    pub color_depth: Option<ColorDepth>,
+    pub colors: BTreeMap<String, ColorSpec>,
+    /// [keys] 표. 명령 목록이 있어야 해석할 수 있으므로 Keymap::from_config 에서 읽는다.
+    pub keys: Option<toml::value::Table>,
+    /// 로그 파일 위치. 시작할 때만 적용된다.
+    pub log_file: PathBuf,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            tab_width: 8,
+            wrap: false,
+            line_numbers: LineNumbers::Off,
+            encoding: Encoding::UTF8,
+            backup: BackupPolicy::None,
+            theme: String::from("default"),
+            color_depth: None,
+            colors: BTreeMap::new(),
+            keys: None,
+            log_file: PathBuf::from("dev.log"),
+        }
+    }
+}
+
+/// tab_width 로 쓸 수 있는 범위
+pub const TAB_WIDTH_RANGE: std::ops::RangeInclusive<i64> = 1..=16;
+
+/**
+ * 사용자 설정 파일 위치. $XDG_CONFIG_HOME/mdedit/config.toml, XDG_CONFIG_HOME 이 없으면 ~/.config 아래.
+ */
+pub fn config_path() -> Option<PathBuf> {
+    let base = match env::var_os("XDG_CONFIG_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => PathBuf::from(env::var_os("HOME")?).join(".config"),
+    };
+
+    Some(base.join("mdedit").join("config.toml"))
+}
+
+/**
+    설정 파일을 읽는다. 파일이 없으면 기본 설정을 쓴다.
+
+    # Return
+    * (설정, 사용자에게 보여줄 문제 목록)
+*/
+pub fn load() -> (Config, Vec<String>) {
+    let path = match config_path() {
+        Some(path) => path,
+        None => return (Config::default(), Vec::new()),
+    };
+
+    match fs::read_to_string(&path) {
+        Ok(text) => {
+            info!("Load config: {:?}", path);
+            let (config, problems) = parse(&text);
+            let problems = problems
+                .into_iter()
+                .map(|problem| format!("{}: {}", path.display(), problem))
+                .collect();
+            (config, problems)
+        }
+        Err(e) if e.kind() == io::ErrorKind::NotFound => (Config::default(), Vec::new()),
+        Err(e) => (
+            Config::default(),
+            vec![format!("{}: {}", path.display(), e)],
+        ),
+    }
+}
+
+pub fn parse(text: &str) -> (Config, Vec<String>) {
+    match text.parse::<toml::Value>() {
+        Ok(toml::Value::Table(table)) => from_table(&table),
+        Ok(_) => (
+            Config::default(),
+            vec![String::from("config must be a table")],
+        ),
+        Err(e) => (Config::default(), vec![e.to_string()]),
+    }
+}
+
+fn from_table(table: &toml::value::Table) -> (Config, Vec<String>) {
+    let mut config = Config::default();
+    let mut problems = Vec::new();
+
+    check_keys(
+        "",
+        table,
+        &["editor", "file", "display", "colors", "keys", "log"],
+        &mut problems,
+    );
+
+    if let Some(editor) = section(table, "editor", &mut problems) {
+        check_keys(
+            "editor.",
+            editor,
+            &["tab_width", "wrap", "line_numbers"],
+            &mut problems,
+        );
+
+        match editor.get("tab_width") {
+            None => {}
+            Some(toml::Value::Integer(n)) if TAB_WIDTH_RANGE.contains(n) => {
+                config.tab_width = *n as u16
+            }
+            Some(value) => problems.push(format!(
+                "editor.tab_width must be a number from {} to {} (got {})",
+                TAB_WIDTH_RANGE.start(),
+                TAB_WIDTH_RANGE.end(),
+                value
+            )),
+        }
+
+        match editor.get("wrap") {
+            None => {}
+            Some(toml::Value::Boolean(wrap)) => {
+                config.wrap = *wrap;
+                if *wrap {
+                    problems.push(String::from(
+                        "editor.wrap: soft wrap is not supported yet, long lines are cut at the window edge",
+                    ));
+                }
+            }
+            Some(value) => {
+                problems.push(format!("editor.wrap must be true or false (got {})", value))
+            }
+        }
+
+        if let Some(name) = string(editor, "editor.line_numbers", &mut problems) {
+            match name.to_ascii_lowercase().as_str() {
+                "off" => config.line_numbers = LineNumbers::Off,
+                "absolute" => config.line_numbers = LineNumbers::Absolute,
+                "relative" => config.line_numbers = LineNumbers::Relative,
+                _ => problems.push(format!(
+                    "editor.line_numbers must be \"off\", \"absolute\" or \"relative\" (got \"{}\")",
+                    name
+                )),
+            }
+        }
+    }
+
+    if let Some(file) = section(table, "file", &mut problems) {
+        check_keys("file.", file, &["encoding", "backup"], &mut problems);
+
+        if let Some(name) = string(file, "file.encoding", &mut problems) {
+            match Encoding::from_name(name) {
+                Some(encoding) => config.encoding = encoding,
+                None => problems.push(format!(
+                    "file.encoding: unsupported encoding \"{}\" (supported: utf-8, utf-8-bom, utf-16le, utf-16be, cp949)",
+                    name
+                )),
+            }
+        }
+
+        if let Some(name) = string(file, "file.backup", &mut problems) {
+            match name.to_ascii_lowercase().as_str() {
+                "none" => config.backup = BackupPolicy::None,
+                "simple" => config.backup = BackupPolicy::Simple,
+                "timestamp" => config.backup = BackupPolicy::Timestamp,
+                _ => problems.push(format!(
+                    "file.backup must be \"none\", \"simple\" or \"timestamp\" (got \"{}\")",
+                    name
+                )),
+            }
+        }
+    }
+
+    if let Some(display) = section(table, "display", &mut problems) {
+        check_keys(
+            "display.",
+            display,
+            &["theme", "color_depth"],
+            &mut problems,
+        );
+
+        // 테마 이름은 에디터가 테마를 만들 때 확인한다.
+        if let Some(name) = string(display, "display.theme", &mut problems) {
+            config.theme = name.to_ascii_lowercase();
+        }
+
+        if let Some(name) = string(display, "display.color_depth", &mut problems) {
+            match name.to_ascii_lowercase().as_str() {
+                "auto" => config.color_depth = None,
+                "16" => config.color_depth = Some(ColorDepth::Ansi16),
+                "256" => config.color_depth = Some(ColorDepth::Ansi256),
+                "truecolor" | "24bit" => config.color_depth = Some(ColorDepth::TrueColor),
+                _ => problems.push(format!(
+                    "display.color_depth must be \"auto\", \"16\", \"256\" or \"truecolor\" (got \"{}\")",
+                    name
+                )),
+            }
+        }
+    }
+
+    if let Some(colors) = section(table, "colors", &mut problems) {
+        for (role, value) in colors {
+            match color_spec(value) {
+                Ok(spec) => {
+                    config.colors.insert(role.clone(), spec);
+                }
+                Err(e) => problems.push(format!("colors.{}: {}", role, e)),
+            }
+        }
+    }
+
+    config.keys = section(table, "keys", &mut problems).cloned();
+
+    if let Some(log) = section(table, "log", &mut problems) {
+        check_keys("log.", log, &["file"], &mut problems);
+        if let Some(file) = string(log, "log.file", &mut problems) {
+            config.log_file = PathBuf::from(file);
+        }
+    }
+
+    (config, problems)
+}
+
+fn section<'a>(
+    table: &'a toml::value::Table,
+    name: &str,
+    problems: &mut Vec<String>,
+) -> Option<&'a toml::value::Table> {
+    match table.get(name) {
+        None => None,
+        Some(toml::Value::Table(section)) => Some(section),
+        Some(_) => {
+            problems.push(format!("[{}] must be a table", name));
+            None
+        }
+    }
+}
+
+fn string<'a>(
+    table: &'a toml::value::Table,
+    name: &str,
+    problems: &mut Vec<String>,
+) -> Option<&'a str> {
+    let key = name.rsplit('.').next().unwrap_or(name);
+    match table.get(key) {
+        None => None,
+        Some(toml::Value::String(s)) => Some(s),
+        Some(value) => {
+            problems.push(format!("{} must be a string (got {})", name, value));
+            None
+        }
+    }
+}
+
+/**
+ * 오타를 알려주기 위해 모르는 항목을 문제 목록에 넣는다.
+ */
+fn check_keys(
+    prefix: &str,
+    table: &toml::value::Table,
+    known: &[&str],
+    problems: &mut Vec<String>,
+) {
+    for key in table.keys() {
+        if !known.contains(&key.as_str()) {
+            problems.push(format!("unknown setting '{}{}'", prefix, key));
+        }
+    }
+}
+
+fn color_spec(value: &toml::Value) -> Result<ColorSpec, String> {
+    let table = match value {
+        toml::Value::Table(table) => table,
+        _ => {
+            return Err(String::from(
+                "must be a table like { fg = \"white\", bg = \"blue\" }",
+            ))
+        }
+    };
+
+    let mut spec = ColorSpec { fg: None, bg: None };
+    for (key, value) in table {
+        let color = Some(parse_color(value)?);
+        match key.as_str() {
+            "fg" => spec.fg = color,
+            "bg" => spec.bg = color,
+            _ => return Err(format!("unknown key '{}' (expected fg or bg)", key)),
+        }
+    }
+    Ok(spec)
+}
+
+/**
+ * 색 이름("dark_cyan"), "#rrggbb", 256색 번호(0~255) 를 읽는다.
+ */
+pub fn parse_color(value: &toml::Value) -> std::result::Result<Color, String> {
+    let name = match value {
+        toml::Value::Integer(n) if (0..=255).contains(n) => return Ok(Color::AnsiValue(*n as u8)),
+        toml::Value::String(name) => name.to_ascii_lowercase().replace(['-', ' '], "_"),
+        _ => return Err(format!("invalid color {}", value)),
+    };
+
+    if let Some(hex) = name.strip_prefix('#') {
+        if hex.len() == 6 {
+            if let Ok(rgb) = u32::from_str_radix(hex, 16) {
+                return Ok(Color::Rgb {
+                    r: (rgb >> 16) as u8,
+                    g: (rgb >> 8) as u8,
+                    b: rgb as u8,
+                });
+            }
+        }
+        return Err(format!("invalid color \"{}\" (expected #rrggbb)", name));
+    }
+
+    let color = match name.as_str() {
+        "reset" | "default" => Color::Reset,
+        "black" => Color::Black,
+        "dark_grey" | "dark_gray" => Color::DarkGrey,
+        "red" => Color::Red,
+        "dark_red" => Color::DarkRed,
+        "green" => Color::Green,
+        "dark_green" => Color::DarkGreen,
+        "yellow" => Color::Yellow,
+        "dark_yellow" => Color::DarkYellow,
+        "blue" => Color::Blue,
+        "dark_blue" => Color::DarkBlue,
+        "magenta" => Color::Magenta,
+        "dark_magenta" => Color::DarkMagenta,
+        "cyan" => Color::Cyan,
+        "dark_cyan" => Color::DarkCyan,
+        "white" => Color::White,
+        "grey" | "gray" => Color::Grey,
+        _ => return Err(format!("unknown color \"{}\"", name)),
+    };
+    Ok(color)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let (config, problems) = parse(
+            r##"
+            [editor]
+            tab_width = 4
+            line_numbers = "Relative"
+
+            [file]
+            encoding = "EUC-KR"
+            backup = "timestamp"
+
+            [display]
+            theme = "Classic"
+            color_depth = "256"
+
+            [colors]
+            status = { fg = "white", bg = "#00aaaa" }
+            menu = { bg = 17 }
+
+            [keys]
+            preset = "wordstar"
+            "##,
+        );
+        assert!(problems.is_empty(), "{:?}", problems);
+        assert_eq!(config.tab_width, 4);
+        assert_eq!(config.line_numbers, LineNumbers::Relative);
+        assert_eq!(config.encoding.label(), "CP949");
+        assert_eq!(config.backup, BackupPolicy::Timestamp);
+        assert_eq!(config.theme, "classic");
+        assert_eq!(config.color_depth, Some(ColorDepth::Ansi256));
+        assert_eq!(
+            config.colors["status"],
+            ColorSpec {
+                fg: Some(Color::White),
+                bg: Some(Color::Rgb {
+                    r: 0,
+                    g: 0xaa,
+                    b: 0xaa
+                })
+            }
+        );
+        assert_eq!(config.colors["menu"].bg, Some(Color::AnsiValue(17)));
+        assert!(config.keys.is_some());
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let (config, problems) = parse(
+            r#"
+            [editor]
+            tab_width = 0
+            tabwidth = 4
+            wrap = "yes"
+            line_numbers = 1
+
+            [file]
+            encoding = "latin-9"
+
+            [colors]
+            status = { fg = "purple" }
+            "#,
+        );
+        assert_eq!(config, Config::default());
+
+        let has = |s: &str| problems.iter().any(|p| p.contains(s));
+        assert!(has(
+            "editor.tab_width must be a number from 1 to 16 (got 0)"
+        ));
+        assert!(has("unknown setting 'editor.tabwidth'"));
+        assert!(has("editor.wrap must be true or false"));
+        assert!(has("editor.line_numbers must be a string"));
+        assert!(has("unsupported encoding \"latin-9\""));
+        assert!(has("colors.status: unknown color \"purple\""));
+        assert_eq!(problems.len(), 6);
+    }
+
+    #[test]
+    fn test_wrap_not_supported() {
+        let (config, problems) = parse("[editor]\nwrap = true\n");
+        assert!(config.wrap);
+        assert_eq!(
+            problems,
+            vec!["editor.wrap: soft wrap is not supported yet, long lines are cut at the window edge"]
+        );
+        assert!(parse("[editor]\nwrap = false\n").1.is_empty());
+    }
+
+    #[test]
+    fn test_syntax_error() {
+        let (config, problems) = parse("[editor\n");
+        assert_eq!(config, Config::default());
+        assert!(problems[0].contains("line 1"));
+    }
+}
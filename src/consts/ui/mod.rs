@@ -3,15 +3,24 @@ use crossterm::style::Color;
 pub const MENU_COLOR: Color = Color::White;
 pub const MENU_BGCOLOR: Color = Color::DarkCyan;
 pub const MENU_BGCOLOR_SELECTED: Color = Color::Black;
+pub const MENU_COLOR_DISABLED: Color = Color::Grey;
+pub const MENU_CHECK_MARK: char = '✓';
+pub const MENU_SUBMENU_MARK: &str = "►";
 
 pub const DLG_BGCOLOR: Color = Color::Grey;
 pub const DLG_COLOR: Color = Color::Black;
 
+pub const STATUS_COLOR: Color = Color::Black;
+pub const STATUS_BGCOLOR: Color = Color::DarkCyan;
+
+pub const GUTTER_COLOR: Color = Color::DarkGrey;
+pub const GUTTER_BGCOLOR: Color = Color::Reset;
+
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum MenuCmd {
     None,
     Refresh,
     CloseMenu,
-    Exit,
-    About,
+    /// 명령 실행. 값은 CommandRegistry 에 등록된 명령 id
+    Run(&'static str),
 }
@@ -0,0 +1,420 @@
+use super::encoding::{Charset, Encoding};
+use super::keymap::KeyBinding;
+use super::line_ending::LineEnding;
+use super::ui::gutter::GutterMode;
+use super::ui::split::SplitDirection;
+use super::Editor;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/// 명령 id (ex: "file.save"). 메뉴, 키 바인딩이 모두 이 id 로 명령을 가리킨다.
+pub type CommandId = &'static str;
+
+/// Grow Pane / Shrink Pane 한 번에 바꾸는 창 크기 (%)
+const PANE_RESIZE_STEP: i16 = 5;
+
+/// Window 메뉴의 문서 목록 항목. n 번째 명령이 n 번째 문서로 바꾼다.
+pub const WINDOW_LIST: [CommandId; 9] = [
+    "window.document_1",
+    "window.document_2",
+    "window.document_3",
+    "window.document_4",
+    "window.document_5",
+    "window.document_6",
+    "window.document_7",
+    "window.document_8",
+    "window.document_9",
+];
+
+/**
+ * 에디터 명령 하나. 메뉴 항목, 키 바인딩이 모두 같은 명령을 실행한다.
+ */
+pub struct Command {
+    pub id: CommandId,
+    /// 메뉴에 표시하는 이름. '&' 바로 뒤의 글자가 단축 글자가 된다.
+    pub name: &'static str,
+    /// 기본 키 바인딩. 설정 파일의 [keys] 로 바꿀 수 있다. (keymap.rs)
+    pub keys: Vec<KeyBinding>,
+    /// 메뉴가 열려 있거나 팝업이 떠 있어도 키 바인딩이 동작하는지 여부
+    pub global: bool,
+    /// 실행할 수 있는 상태인지 여부. 비활성 명령은 메뉴에서 회색으로 표시된다.
+    pub enabled: fn(&Editor) -> bool,
+    /// 켜고 끄는 명령이면 현재 켜져 있는지 여부. 메뉴에 체크 표시로 나온다.
+    pub checked: Option<fn(&Editor) -> bool>,
+    pub handler: fn(&mut Editor),
+}
+
+impl Command {
+    fn new(id: CommandId, name: &'static str, handler: fn(&mut Editor)) -> Command {
+        Command {
+            id,
+            name,
+            keys: Vec::new(),
+            global: false,
+            enabled: |_| true,
+            checked: None,
+            handler,
+        }
+    }
+
+    fn key(mut self, modifiers: KeyModifiers, code: KeyCode) -> Command {
+        self.keys.push(KeyBinding::new(modifiers, code));
+        self
+    }
+
+    fn global(mut self) -> Command {
+        self.global = true;
+        self
+    }
+
+    fn enabled(mut self, enabled: fn(&Editor) -> bool) -> Command {
+        self.enabled = enabled;
+        self
+    }
+
+    fn checked(mut self, checked: fn(&Editor) -> bool) -> Command {
+        self.checked = Some(checked);
+        self
+    }
+}
+
+/**
+ * 에디터의 모든 명령 목록. 새 기능은 여기에 명령을 하나 등록하면 메뉴와 키 바인딩에서 쓸 수 있다.
+ */
+pub struct CommandRegistry {
+    commands: Vec<Command>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> CommandRegistry {
+        let mut registry = CommandRegistry {
+            commands: Vec::new(),
+        };
+        registry.register_builtin();
+        registry
+    }
+
+    fn register_builtin(&mut self) {
+        use KeyCode::*;
+        const NONE: KeyModifiers = KeyModifiers::NONE;
+        const CTRL: KeyModifiers = KeyModifiers::CONTROL;
+        const ALT: KeyModifiers = KeyModifiers::ALT;
+
+        self.register(
+            Command::new("file.new", "&New", |ed| ed.new_document()).key(CTRL, Char('n')),
+        );
+        self.register(
+            Command::new("file.save", "&Save", |ed| ed.handle_save())
+                .key(CTRL, Char('s'))
+                .enabled(|ed| ed.doc().modified && !ed.doc().read_only),
+        );
+        self.register(Command::new("file.save_as", "Save &As...", |ed| {
+            ed.handle_save_as()
+        }));
+        // 모든 줄을 한 가지 줄 끝 문자로 바꾼다. 섞여 있으면 어느 것도 체크하지 않는다.
+        self.register(
+            Command::new("file.line_ending_lf", "&LF (Unix)", |ed| {
+                ed.set_line_ending(LineEnding::Lf)
+            })
+            // 줄 끝 문자는 바이트를 직접 고치는 동안(Hex) 바꿀 수 없다. 아래 명령들도 같다.
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.uniform_line_ending() == Some(LineEnding::Lf)),
+        );
+        self.register(
+            Command::new("file.line_ending_crlf", "&CRLF (Windows)", |ed| {
+                ed.set_line_ending(LineEnding::CrLf)
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.uniform_line_ending() == Some(LineEnding::CrLf)),
+        );
+        self.register(
+            Command::new("file.line_ending_cr", "C&R (Classic Mac)", |ed| {
+                ed.set_line_ending(LineEnding::Cr)
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.uniform_line_ending() == Some(LineEnding::Cr)),
+        );
+        self.register(
+            Command::new("file.final_newline", "&Final Newline", |ed| {
+                ed.toggle_final_newline()
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().final_newline),
+        );
+        // 저장할 때의 인코딩을 바꾼다.
+        self.register(
+            Command::new("file.encoding_utf8", "&UTF-8", |ed| {
+                ed.set_encoding(Encoding::new(Charset::Utf8, false))
+            })
+            // Hex 에서는 바이트가 곧 저장할 내용이므로 인코딩을 바꿀 수 없다.
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding == Encoding::new(Charset::Utf8, false)),
+        );
+        self.register(
+            Command::new("file.encoding_utf8_bom", "UTF-8 with &BOM", |ed| {
+                ed.set_encoding(Encoding::new(Charset::Utf8, true))
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding == Encoding::new(Charset::Utf8, true)),
+        );
+        self.register(
+            Command::new("file.encoding_utf16le", "UTF-16&LE", |ed| {
+                ed.set_encoding(Encoding::new(Charset::Utf16Le, true))
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Utf16Le),
+        );
+        self.register(
+            Command::new("file.encoding_utf16be", "UTF-16B&E", |ed| {
+                ed.set_encoding(Encoding::new(Charset::Utf16Be, true))
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Utf16Be),
+        );
+        self.register(
+            Command::new("file.encoding_cp949", "&CP949 (EUC-KR)", |ed| {
+                ed.set_encoding(Encoding::new(Charset::Cp949, false))
+            })
+            .enabled(|ed| !ed.doc().read_only && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Cp949),
+        );
+        // 자동으로 알아낸 인코딩이 틀렸을 때 파일을 다시 읽는다.
+        self.register(
+            Command::new("file.reopen_utf8", "&UTF-8", |ed| {
+                ed.reopen_with_encoding(Charset::Utf8)
+            })
+            .enabled(|ed| ed.doc().filename.is_some() && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Utf8),
+        );
+        self.register(
+            Command::new("file.reopen_utf16le", "UTF-16&LE", |ed| {
+                ed.reopen_with_encoding(Charset::Utf16Le)
+            })
+            .enabled(|ed| ed.doc().filename.is_some() && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Utf16Le),
+        );
+        self.register(
+            Command::new("file.reopen_utf16be", "UTF-16B&E", |ed| {
+                ed.reopen_with_encoding(Charset::Utf16Be)
+            })
+            .enabled(|ed| ed.doc().filename.is_some() && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Utf16Be),
+        );
+        self.register(
+            Command::new("file.reopen_cp949", "&CP949 (EUC-KR)", |ed| {
+                ed.reopen_with_encoding(Charset::Cp949)
+            })
+            .enabled(|ed| ed.doc().filename.is_some() && ed.doc().hex.is_none())
+            .checked(|ed| ed.doc().encoding.charset == Charset::Cp949),
+        );
+        self.register(
+            Command::new("file.close", "&Close", |ed| ed.handle_close()).key(CTRL, Char('w')),
+        );
+        self.register(
+            Command::new("file.exit", "E&xit", |ed| ed.handle_exit())
+                .key(CTRL, Char('q'))
+                .key(NONE, F(12))
+                .global(),
+        );
+
+        // 16진수 보기에서 고친 바이트는 되돌리기 기록에 없다.
+        self.register(
+            Command::new("edit.undo", "&Undo", |ed| ed.undo())
+                .key(CTRL, Char('z'))
+                .enabled(|ed| {
+                    ed.doc().history.can_undo() && !ed.doc().read_only && ed.doc().hex.is_none()
+                }),
+        );
+        self.register(
+            Command::new("edit.redo", "&Redo", |ed| ed.redo())
+                .key(CTRL, Char('y'))
+                .enabled(|ed| {
+                    ed.doc().history.can_redo() && !ed.doc().read_only && ed.doc().hex.is_none()
+                }),
+        );
+        self.register(
+            Command::new("edit.toggle_overwrite", "&Overwrite Mode", |ed| {
+                ed.handle_insertkey()
+            })
+            .key(NONE, Insert)
+            .checked(|ed| ed.overwrite),
+        );
+        // 읽기 전용으로 연 파일은 고칠 수 없다.
+        self.register(
+            Command::new("edit.tab", "Tab", |ed| ed.handle_tab())
+                .key(NONE, Tab)
+                .enabled(|ed| !ed.doc().read_only),
+        );
+        self.register(
+            Command::new("edit.newline", "New Line", |ed| ed.handle_enterkey())
+                .key(NONE, Enter)
+                .enabled(|ed| !ed.doc().read_only),
+        );
+        self.register(
+            Command::new("edit.backspace", "Backspace", |ed| ed.handle_backspace())
+                .key(NONE, Backspace)
+                .key(KeyModifiers::SHIFT, Backspace)
+                .key(CTRL, Backspace)
+                .key(ALT, Backspace)
+                .enabled(|ed| !ed.doc().read_only),
+        );
+
+        self.register(
+            Command::new("cursor.left", "Left", |ed| ed.handle_leftkey()).key(NONE, Left),
+        );
+        self.register(
+            Command::new("cursor.right", "Right", |ed| ed.handle_rightkey()).key(NONE, Right),
+        );
+        self.register(Command::new("cursor.up", "Up", |ed| ed.handle_upkey()).key(NONE, Up));
+        self.register(
+            Command::new("cursor.down", "Down", |ed| ed.handle_downkey()).key(NONE, Down),
+        );
+
+        self.register(
+            Command::new("view.line_numbers", "&Line Numbers", |ed| {
+                ed.toggle_line_numbers()
+            })
+            .checked(|ed| ed.gutter.mode != GutterMode::Off),
+        );
+        // 소프트 줄바꿈은 아직 없다. 설정 파일의 editor.wrap 만 체크로 보여 준다.
+        self.register(
+            Command::new("view.word_wrap", "&Word Wrap", |_| {})
+                .enabled(|_| false)
+                .checked(|ed| ed.config.wrap),
+        );
+        self.register(
+            Command::new("view.absolute_numbers", "&Absolute", |ed| {
+                ed.set_gutter_mode(GutterMode::Absolute)
+            })
+            .checked(|ed| ed.gutter.mode == GutterMode::Absolute),
+        );
+        self.register(
+            Command::new("view.relative_numbers", "&Relative", |ed| {
+                ed.set_gutter_mode(GutterMode::Relative)
+            })
+            .checked(|ed| ed.gutter.mode == GutterMode::Relative),
+        );
+        self.register(
+            Command::new("view.hex", "&Hex", |ed| ed.toggle_hex_view())
+                .checked(|ed| ed.doc().hex.is_some()),
+        );
+
+        // 대부분의 터미널은 Ctrl+Tab 을 Tab 과 구분하지 못하므로 F6 도 연결한다.
+        self.register(
+            Command::new("window.next", "&Next Window", |ed| ed.next_document())
+                .key(CTRL, Tab)
+                .key(NONE, F(6))
+                .enabled(|ed| ed.documents.len() > 1),
+        );
+        self.register(
+            Command::new("window.previous", "&Previous Window", |ed| {
+                ed.previous_document()
+            })
+            .key(CTRL | KeyModifiers::SHIFT, BackTab)
+            .key(KeyModifiers::SHIFT, F(6))
+            .enabled(|ed| ed.documents.len() > 1),
+        );
+        // 창 나누기 키는 Emacs 의 C-x 2 / C-x 3 / C-x 0 을 따른다. (Alt+글자는 메뉴 단축 글자)
+        self.register(
+            Command::new("window.split_horizontal", "Split &Horizontal", |ed| {
+                ed.split_pane(SplitDirection::Horizontal)
+            })
+            .key(ALT, Char('2')),
+        );
+        self.register(
+            Command::new("window.split_vertical", "Split &Vertical", |ed| {
+                ed.split_pane(SplitDirection::Vertical)
+            })
+            .key(ALT, Char('3')),
+        );
+        self.register(
+            Command::new("window.next_pane", "Ne&xt Pane", |ed| ed.next_pane())
+                .key(NONE, F(8))
+                .enabled(|ed| ed.panes.len() > 1),
+        );
+        self.register(
+            Command::new("window.grow_pane", "&Grow Pane", |ed| {
+                ed.resize_pane(PANE_RESIZE_STEP)
+            })
+            .key(ALT, Char('='))
+            .enabled(|ed| ed.panes.len() > 1),
+        );
+        self.register(
+            Command::new("window.shrink_pane", "&Shrink Pane", |ed| {
+                ed.resize_pane(-PANE_RESIZE_STEP)
+            })
+            .key(ALT, Char('-'))
+            .enabled(|ed| ed.panes.len() > 1),
+        );
+        self.register(
+            Command::new("window.close_pane", "&Close Pane", |ed| ed.close_pane())
+                .key(ALT, Char('0'))
+                .enabled(|ed| ed.panes.len() > 1),
+        );
+        // 메뉴에 보이는 이름은 Window 메뉴를 그릴 때 문서 이름으로 바꾼다. (MenuBar::set_window_list)
+        macro_rules! window_document {
+            ($n:expr) => {
+                self.register(
+                    Command::new(WINDOW_LIST[$n], WINDOW_LIST[$n], |ed| {
+                        ed.switch_document($n)
+                    })
+                    .enabled(|ed| ed.documents.len() > $n)
+                    .checked(|ed| ed.current == $n),
+                );
+            };
+        }
+        window_document!(0);
+        window_document!(1);
+        window_document!(2);
+        window_document!(3);
+        window_document!(4);
+        window_document!(5);
+        window_document!(6);
+        window_document!(7);
+        window_document!(8);
+
+        self.register(Command::new("options.display", "&Display...", |ed| {
+            ed.handle_display_options()
+        }));
+        self.register(Command::new(
+            "options.reload_settings",
+            "&Reload Settings",
+            |ed| ed.reload_settings(),
+        ));
+
+        self.register(Command::new("menu.open", "Menu", |ed| ed.handle_menu()).key(NONE, F(10)));
+        self.register(Command::new("help.about", "&About", |ed| ed.handle_help()).key(NONE, F(1)));
+    }
+
+    fn register(&mut self, command: Command) {
+        self.commands.push(command);
+    }
+
+    pub fn get(&self, id: &str) -> Option<&Command> {
+        self.commands.iter().find(|command| command.id == id)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Command> {
+        self.commands.iter()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_registry() {
+        let registry = CommandRegistry::new();
+
+        let save = KeyBinding::new(KeyModifiers::CONTROL, KeyCode::Char('s'));
+        assert_eq!(registry.get("file.save").unwrap().keys, vec![save]);
+        assert!(registry.get("file.exit").unwrap().global);
+        assert!(registry.get("view.line_numbers").unwrap().keys.is_empty());
+        assert!(registry.get("no.such.command").is_none());
+
+        // id 는 겹치면 안 된다
+        for command in registry.iter() {
+            assert_eq!(registry.iter().filter(|c| c.id == command.id).count(), 1);
+        }
+    }
+}
@@ -1,11 +1,39 @@
+#[derive(Clone, Copy)]
 pub struct Cursor {
     pub x: u16,
     y: u16,
+    /// 본문 왼쪽에 있는 줄 번호 영역 너비
+    x_offset: u16,
+    /// 화면 맨 위에 보이는 줄의 인덱스
+    top: u16,
+    /// 커서가 있는 창의 화면 위치 (창을 나누지 않았으면 메뉴 바 아래 (0, 1))
+    origin_x: u16,
+    origin_y: u16,
 }
 
 impl Cursor {
     pub fn new() -> Cursor {
-        Cursor { x: 0, y: 0 }
+        Cursor {
+            x: 0,
+            y: 0,
+            x_offset: 0,
+            top: 0,
+            origin_x: 0,
+            origin_y: 1,
+        }
+    }
+
+    pub fn set_origin(&mut self, x: u16, y: u16) {
+        self.origin_x = x;
+        self.origin_y = y;
+    }
+
+    pub fn set_x_offset(&mut self, x_offset: u16) {
+        self.x_offset = x_offset;
+    }
+
+    pub fn get_x_offset(&self) -> u16 {
+        self.x_offset
     }
 
     pub fn move_left(&mut self, x: u16) {
@@ -26,17 +54,126 @@ impl Cursor {
         }
     }
 
-    pub fn move_down(&mut self, height: u16) {
-        if self.y < height {
+    /**
+        # Arguments
+        * `max_y` - 마지막 줄의 인덱스
+    */
+    pub fn move_down(&mut self, max_y: u16) {
+        if self.y < max_y {
             self.y += 1;
         }
     }
 
+    pub fn set_y(&mut self, y: u16) {
+        self.y = y;
+    }
+
     pub fn get_y(&self) -> u16 {
         self.y
     }
 
+    pub fn get_top(&self) -> u16 {
+        self.top
+    }
+
+    /**
+        커서가 화면 밖에 있으면 보이도록 화면을 스크롤한다.
+
+        # Arguments
+        * `height` - 본문 영역 높이
+
+        # Return
+        * 스크롤 여부 (true 면 화면 전체를 다시 그려야 함)
+    */
+    pub fn scroll_into_view(&mut self, height: u16) -> bool {
+        let old_top = self.top;
+
+        if self.y < self.top {
+            self.top = self.y;
+        } else if height > 0 && self.y >= self.top + height {
+            self.top = self.y - height + 1;
+        }
+
+        old_top != self.top
+    }
+
+    /**
+        커서는 그대로 두고 화면만 스크롤한다. (마우스 휠)
+        커서가 화면 밖으로 나가면 화면 안쪽 가장 가까운 줄로 옮긴다.
+
+        # Arguments
+        * `delta` - 스크롤할 줄 수. 음수면 위로
+        * `max_top` - top 의 최대값 (마지막 줄의 인덱스)
+        * `height` - 본문 영역 높이
+    */
+    pub fn scroll(&mut self, delta: i32, max_top: u16, height: u16) {
+        let new_top = (self.top as i32 + delta).clamp(0, max_top as i32);
+        self.top = new_top as u16;
+
+        if self.y < self.top {
+            self.y = self.top;
+        } else if height > 0 && self.y >= self.top + height {
+            self.y = self.top + height - 1;
+        }
+    }
+
+    pub fn screen_x(&self) -> u16 {
+        self.origin_x + self.x_offset + self.x
+    }
+
     pub fn screen_y(&self) -> u16 {
-        self.y + 1
+        self.origin_y + self.y - self.top
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scroll_into_view() {
+        let mut c = Cursor::new();
+        assert!(!c.scroll_into_view(10));
+
+        c.set_y(15);
+        assert!(c.scroll_into_view(10));
+        assert_eq!(c.get_top(), 6);
+        assert_eq!(c.screen_y(), 10);
+
+        c.set_y(2);
+        assert!(c.scroll_into_view(10));
+        assert_eq!(c.get_top(), 2);
+        assert_eq!(c.screen_y(), 1);
+    }
+
+    #[test]
+    fn test_scroll() {
+        let mut c = Cursor::new();
+        c.scroll(3, 20, 10);
+        assert_eq!(c.get_top(), 3);
+        assert_eq!(c.get_y(), 3);
+
+        c.scroll(30, 20, 10);
+        assert_eq!(c.get_top(), 20);
+
+        c.set_y(25);
+        c.scroll(-15, 30, 10);
+        assert_eq!(c.get_top(), 5);
+        assert_eq!(c.get_y(), 14);
+
+        c.scroll(-15, 30, 10);
+        assert_eq!(c.get_top(), 0);
+    }
+    #[test]
+    fn test_origin() {
+        let mut c = Cursor::new();
+        c.set_x_offset(4);
+        c.x = 2;
+        c.set_y(7);
+        c.scroll_into_view(5);
+        assert_eq!((c.screen_x(), c.screen_y()), (6, 5));
+
+        c.set_origin(41, 13);
+        assert_eq!((c.screen_x(), c.screen_y()), (47, 17));
     }
 }
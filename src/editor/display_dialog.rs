@@ -0,0 +1,151 @@
+use crate::config::{ColorDepth, TAB_WIDTH_RANGE};
+use crate::editor::ui::dialog::{
+    Button, CheckBox, Dialog, DialogResult, Label, ListBox, RadioGroup, TextInput, WidgetValue,
+};
+use crate::editor::ui::theme::THEME_NAMES;
+
+/// Options > Display 대화상자의 id
+pub const ID: &str = "display";
+
+/// 색 수 선택지. None 은 터미널에서 추측
+const COLOR_DEPTHS: [(&str, Option<ColorDepth>); 4] = [
+    ("Auto", None),
+    ("16 colors", Some(ColorDepth::Ansi16)),
+    ("256 colors", Some(ColorDepth::Ansi256)),
+    ("True color", Some(ColorDepth::TrueColor)),
+];
+
+/**
+ * Options > Display 에서 바꿀 수 있는 설정
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DisplayOptions {
+    pub theme: &'static str,
+    pub color_depth: Option<ColorDepth>,
+    pub line_numbers: bool,
+    pub tab_width: u16,
+}
+
+/**
+ * Options > Display 대화상자. 테마 목록에서 고르면 바로 미리 보여준다.
+ */
+pub fn new(current: &DisplayOptions) -> Dialog {
+    let theme = THEME_NAMES
+        .iter()
+        .position(|name| *name == current.theme)
+        .unwrap_or(0);
+    let depth = COLOR_DEPTHS
+        .iter()
+        .position(|(_, depth)| *depth == current.color_depth)
+        .unwrap_or(0);
+    let depth_labels: Vec<&str> = COLOR_DEPTHS.iter().map(|(label, _)| *label).collect();
+
+    Dialog::new(ID, "Display", 46, 11)
+        .add("theme_label", 2, 1, Label::new("Color theme:"))
+        .add("theme", 2, 2, ListBox::new(THEME_NAMES, theme, 20, 4))
+        .add("depth_label", 26, 1, Label::new("Colors:"))
+        .add("depth", 26, 2, RadioGroup::new(&depth_labels, depth))
+        .add(
+            "line_numbers",
+            2,
+            7,
+            CheckBox::new("Line numbers", current.line_numbers),
+        )
+        .add("tab_label", 26, 7, Label::new("Tab stops:"))
+        .add(
+            "tab_width",
+            37,
+            7,
+            TextInput::new(4, &current.tab_width.to_string()),
+        )
+        .add("ok", 12, 9, Button::new("OK"))
+        .add("cancel", 22, 9, Button::new("Cancel"))
+        .default_button("ok")
+}
+
+/**
+ * 테마 목록의 값을 테마 이름으로 바꾼다. 미리 보기에 쓴다.
+ */
+pub fn theme_name(value: &WidgetValue) -> Option<&'static str> {
+    match value {
+        WidgetValue::Selected(Some(idx)) => THEME_NAMES.get(*idx).copied(),
+        _ => None,
+    }
+}
+
+/**
+대화상자 결과를 설정으로 바꾼다.
+
+# Return
+* 취소했으면 Ok(None), 잘못된 값이 있으면 사용자에게 보여줄 메시지
+*/
+pub fn options(result: &DialogResult) -> Result<Option<DisplayOptions>, String> {
+    if result.button != Some("ok") {
+        return Ok(None);
+    }
+
+    let tab_width = result
+        .text("tab_width")
+        .and_then(|text| text.trim().parse::<i64>().ok())
+        .filter(|n| TAB_WIDTH_RANGE.contains(n))
+        .ok_or_else(|| {
+            format!(
+                "Tab stops must be a number from {} to {}",
+                TAB_WIDTH_RANGE.start(),
+                TAB_WIDTH_RANGE.end()
+            )
+        })?;
+
+    Ok(Some(DisplayOptions {
+        theme: theme_name(result.value("theme")).unwrap_or(THEME_NAMES[0]),
+        color_depth: COLOR_DEPTHS[result.selected("depth").unwrap_or(0)].1,
+        line_numbers: result.checked("line_numbers").unwrap_or(false),
+        tab_width: tab_width as u16,
+    }))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::editor::ui::dialog::DialogEvent;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    fn close(dialog: &mut Dialog, code: KeyCode) -> DialogResult {
+        match dialog.handle_keyinput(KeyModifiers::NONE, code) {
+            DialogEvent::Closed(result) => result,
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_options() {
+        let current = DisplayOptions {
+            theme: "default",
+            color_depth: Some(ColorDepth::Ansi256),
+            line_numbers: false,
+            tab_width: 8,
+        };
+        let mut dialog = new(&current);
+        assert_eq!(
+            dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Down),
+            DialogEvent::Changed("theme")
+        );
+        assert_eq!(theme_name(&dialog.value("theme")), Some("classic"));
+
+        let result = close(&mut dialog, KeyCode::Enter);
+        let expected = DisplayOptions {
+            theme: "classic",
+            ..current.clone()
+        };
+        assert_eq!(options(&result), Ok(Some(expected)));
+        assert_eq!(options(&close(&mut dialog, KeyCode::Esc)), Ok(None));
+
+        // 탭 간격 입력 칸으로 가서 잘못된 값을 넣는다.
+        let mut dialog = new(&current);
+        for _ in 0..3 {
+            dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Tab);
+        }
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('0'));
+        assert!(options(&close(&mut dialog, KeyCode::Enter)).is_err());
+    }
+}
@@ -0,0 +1,238 @@
+use super::cursor::Cursor;
+use super::encoding::{self, Decoded, Encoding};
+use super::error::{EditorError, Operation};
+use super::line_buffer::LineBuffer;
+use super::line_ending::{self, LineEnding};
+use super::swap::{self, SwapFile};
+use super::ui::hex_view::HexView;
+use super::undo::History;
+use crate::check_result;
+use std::path::PathBuf;
+use std::time::Instant;
+
+/**
+열려 있는 문서 하나. 문서마다 내용, 커서와 스크롤 위치, 되돌리기 기록, 파일 정보를 따로 가진다.
+Window 메뉴나 Ctrl+Tab 으로 다른 문서로 바꿔도 그대로 남아 있다.
+*/
+pub struct Document {
+    pub contents: Vec<LineBuffer>,
+    pub cursor: Cursor,
+    pub filename: Option<String>,
+    pub modified: bool,
+    /// 새로 만드는 줄의 줄 끝 문자. 파일을 열면 그 파일에서 가장 많이 쓴 것
+    pub line_ending: LineEnding,
+    /// 마지막 줄 뒤에도 줄 끝 문자를 쓰는지 여부
+    pub final_newline: bool,
+    /// 파일을 읽고 쓸 때의 인코딩
+    pub encoding: Encoding,
+    /// 다른 에디터가 편집 중일 수 있는 파일을 읽기 전용으로 열었는지 여부
+    pub read_only: bool,
+    /// 스왑 파일 위치. 읽기 전용이면 None (스왑 파일을 쓰지 않는다)
+    pub swap_path: Option<PathBuf>,
+    /// 마지막으로 스왑 파일을 쓴 뒤에 내용이 바뀌었는지 여부
+    pub swap_dirty: bool,
+    pub swap_written: Instant,
+    /// 복구할지 묻고 있는 스왑 파일
+    pub stale_swap: Option<(PathBuf, SwapFile)>,
+    /// 열지 묻고 있는, 텍스트 파일이 아닌 것 같은 파일의 내용
+    pub pending_binary: Option<Decoded>,
+    /// View > Hex 로 바이트를 보고 있으면 그 화면. 텍스트로 돌아갈 때 내용에 반영한다.
+    pub hex: Option<HexView>,
+    pub history: History,
+}
+
+impl Document {
+    /**
+    빈 문서를 만든다.
+
+    # Arguments
+    * `encoding` - 저장할 때의 인코딩 (설정의 file.encoding)
+    * `swap_path` - 스왑 파일 위치
+    */
+    pub fn new(encoding: Encoding, swap_path: Option<PathBuf>) -> Document {
+        Document {
+            contents: Vec::from([LineBuffer::new()]),
+            cursor: Cursor::new(),
+            filename: None,
+            modified: false,
+            line_ending: LineEnding::native(),
+            final_newline: true,
+            encoding,
+            read_only: false,
+            swap_path,
+            swap_dirty: false,
+            swap_written: Instant::now(),
+            stale_swap: None,
+            pending_binary: None,
+            hex: None,
+            history: History::new(),
+        }
+    }
+
+    /**
+    `line` 부터 `count` 줄을 고치기 전에 부른다. 고친 뒤에는 end_edit 을 부른다.
+
+    # Arguments
+    * `typing` - 글자 입력이면 같은 줄에 이어서 입력한 글자와 합쳐서 한 번에 되돌린다.
+    */
+    pub fn begin_edit(&mut self, line: usize, count: usize, typing: bool) {
+        self.history.begin(
+            &self.contents,
+            line,
+            count,
+            self.final_newline,
+            self.cursor,
+            typing,
+        );
+    }
+
+    /**
+     * 고친 뒤에 begin_edit 의 `line` 부터 지금 있는 줄 수를 넘긴다.
+     */
+    pub fn end_edit(&mut self, count: usize) {
+        self.history.end(count);
+    }
+
+    /**
+     * 마지막 편집을 되돌린다. 되돌린 뒤의 커서 위치를 돌려준다.
+     */
+    pub fn undo(&mut self) -> Option<Cursor> {
+        self.history
+            .undo(&mut self.contents, &mut self.final_newline, self.cursor)
+    }
+
+    pub fn redo(&mut self) -> Option<Cursor> {
+        self.history
+            .redo(&mut self.contents, &mut self.final_newline, self.cursor)
+    }
+
+    /**
+     * 파일을 새로 읽었으면 그 전의 편집은 되돌릴 수 없다.
+     */
+    pub fn reset_history(&mut self) {
+        self.history = History::new();
+    }
+
+    /**
+     * Window 메뉴와 대화상자에 보여주는 이름
+     */
+    pub fn title(&self) -> &str {
+        self.filename.as_deref().unwrap_or("Untitled")
+    }
+
+    pub fn current_line(&mut self) -> Option<&mut LineBuffer> {
+        self.contents.get_mut(self.cursor.get_y() as usize)
+    }
+
+    pub fn last_line_index(&self) -> u16 {
+        self.contents.len().saturating_sub(1) as u16
+    }
+
+    /**
+     * 파일 내용을 줄로 나눠서 넣는다. 줄마다 줄 끝 문자를 기억해서 저장할 때 그대로 쓴다.
+     */
+    pub fn load_text(&mut self, text: &str) {
+        self.set_text(text);
+        self.reset_history();
+    }
+
+    /**
+     * 내용 전체를 `text` 로 바꾸는 편집을 한다. load_text 와 달리 한 번에 되돌릴 수 있게 기록에 남긴다.
+     */
+    pub fn replace_text(&mut self, text: &str) {
+        let count = self.contents.len();
+        self.begin_edit(0, count, false);
+        self.set_text(text);
+        let count = self.contents.len();
+        self.end_edit(count);
+    }
+
+    fn set_text(&mut self, text: &str) {
+        let split = line_ending::split_lines(text);
+        self.line_ending = split.dominant().unwrap_or_else(LineEnding::native);
+        self.final_newline = split.final_newline();
+        // 마지막 줄 뒤에 줄 끝 문자가 없으면 그 줄 뒤에 줄을 더 만들 때 쓸 것을 정해 둔다.
+        self.contents = split
+            .lines
+            .iter()
+            .map(|(line, ending)| LineBuffer::with_ending(line, ending.unwrap_or(self.line_ending)))
+            .collect();
+    }
+
+    /**
+     * 저장할 파일 내용. 줄마다 그 줄의 줄 끝 문자를 붙인다.
+     */
+    pub fn text(&self) -> String {
+        let mut text = String::new();
+        for (i, line) in self.contents.iter().enumerate() {
+            text.push_str(line.get_buffer());
+            if i + 1 < self.contents.len() || self.final_newline {
+                text.push_str(line.ending().as_str());
+            }
+        }
+        text
+    }
+
+    /**
+     * 파일에 쓸 바이트. View > Hex 에서는 고친 바이트를 그대로 쓴다.
+     * 인코딩으로 나타낼 수 없는 글자가 있으면 그 글자들을 돌려준다.
+     */
+    pub fn file_data(&self) -> Result<Vec<u8>, Vec<char>> {
+        match &self.hex {
+            Some(hex) => Ok(hex.bytes().to_vec()),
+            None => encoding::encode(&self.text(), self.encoding),
+        }
+    }
+
+    /**
+     * 지금 내용을 줄 끝 문자와 함께 스왑 파일에 쓴다.
+     * 바이트를 고치는 동안(View > Hex)에는 텍스트로 돌아간 다음에 쓴다.
+     */
+    pub fn write_swap(&mut self) -> Result<(), EditorError> {
+        self.swap_dirty = false;
+        self.swap_written = Instant::now();
+        if self.hex.is_some() {
+            return Ok(());
+        }
+
+        match &self.swap_path {
+            Some(path) => {
+                let lines = self
+                    .contents
+                    .iter()
+                    .map(|line| (line.get_buffer().as_str(), line.ending()));
+                swap::write(path, lines, self.final_newline)
+                    .map_err(|e| EditorError::new(Operation::WriteSwap, Some(path), e))
+            }
+            None => Ok(()),
+        }
+    }
+
+    /**
+     * 저장했거나 닫을 때 스왑 파일을 지운다.
+     */
+    pub fn remove_swap(&self) {
+        if let Some(path) = &self.swap_path {
+            check_result!(swap::remove(path), "Unable to remove swap file");
+        }
+    }
+
+    /**
+     * 모든 줄이 같은 줄 끝 문자를 쓰면 그것, 섞여 있으면 None.
+     * 줄 끝 문자가 하나도 없으면(줄 끝 없는 한 줄) 새 줄에 쓸 것
+     */
+    pub fn uniform_line_ending(&self) -> Option<LineEnding> {
+        let written = if self.final_newline {
+            self.contents.len()
+        } else {
+            self.contents.len() - 1
+        };
+        let mut endings = self.contents[..written].iter().map(|line| line.ending());
+        let first = endings.next().unwrap_or(self.line_ending);
+        if endings.all(|ending| ending == first) {
+            Some(first)
+        } else {
+            None
+        }
+    }
+}
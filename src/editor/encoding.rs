@@ -0,0 +1,490 @@
+use encoding_rs::{EUC_KR, UTF_16BE, UTF_16LE};
+use std::borrow::Cow;
+use std::convert::TryFrom;
+use unicode_width::UnicodeWidthChar;
+
+/**
+ * 파일의 문자 집합. 버퍼는 항상 UTF-8 이고, 읽고 쓸 때만 바꾼다.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Charset {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    /// EUC-KR 과 그 확장(통합형 한글 코드, MS949). encoding_rs 의 EUC-KR 이 CP949 전체를 다룬다.
+    Cp949,
+}
+
+/**
+ * 파일의 인코딩. BOM 이 있던 파일은 저장할 때도 BOM 을 쓴다.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Encoding {
+    pub charset: Charset,
+    pub bom: bool,
+}
+
+const UTF8_BOM: &[u8] = &[0xef, 0xbb, 0xbf];
+const UTF16LE_BOM: &[u8] = &[0xff, 0xfe];
+const UTF16BE_BOM: &[u8] = &[0xfe, 0xff];
+
+/// UTF-16 인지 추측할 때 살펴보는 앞부분 크기
+const SNIFF_LEN: usize = 1024;
+/// 보존한 바이트가 이 비율(%)을 넘으면 텍스트 파일이 아닌 것으로 본다.
+const BINARY_PERCENT: usize = 10;
+/// `\xNN` 으로 보존하는 바이트 0x00 ~ 0xff 를 나타내는 코드 포인트 U+10FF00 ~ U+10FFFF
+const ESCAPE_BASE: u32 = 0x10ff00;
+
+impl Encoding {
+    pub const UTF8: Encoding = Encoding::new(Charset::Utf8, false);
+
+    pub const fn new(charset: Charset, bom: bool) -> Encoding {
+        Encoding { charset, bom }
+    }
+
+    /**
+     * 파일이 그 문자 집합의 BOM 으로 시작하면 BOM 이 있는 인코딩
+     */
+    pub fn with_bom_of(charset: Charset, bytes: &[u8]) -> Encoding {
+        let bom = Encoding::new(charset, true).bom_bytes();
+        Encoding::new(charset, !bom.is_empty() && bytes.starts_with(bom))
+    }
+
+    /**
+     * 설정 파일에 쓰는 이름. 대소문자는 가리지 않는다.
+     * UTF-16 으로 새로 만드는 파일에는 BOM 을 쓴다.
+     */
+    pub fn from_name(name: &str) -> Option<Encoding> {
+        let encoding = match name.to_ascii_lowercase().as_str() {
+            "utf-8" | "utf8" => Encoding::new(Charset::Utf8, false),
+            "utf-8-bom" | "utf8-bom" => Encoding::new(Charset::Utf8, true),
+            "utf-16le" | "utf16le" => Encoding::new(Charset::Utf16Le, true),
+            "utf-16be" | "utf16be" => Encoding::new(Charset::Utf16Be, true),
+            "cp949" | "ms949" | "uhc" | "euc-kr" | "euckr" => Encoding::new(Charset::Cp949, false),
+            _ => return None,
+        };
+        Some(encoding)
+    }
+
+    /**
+     * 상태 표시줄에 보여주는 이름
+     */
+    pub fn label(self) -> &'static str {
+        match (self.charset, self.bom) {
+            (Charset::Utf8, false) => "UTF-8",
+            (Charset::Utf8, true) => "UTF-8 BOM",
+            (Charset::Utf16Le, _) => "UTF-16LE",
+            (Charset::Utf16Be, _) => "UTF-16BE",
+            (Charset::Cp949, _) => "CP949",
+        }
+    }
+
+    fn bom_bytes(self) -> &'static [u8] {
+        match self.charset {
+            Charset::Utf8 => UTF8_BOM,
+            Charset::Utf16Le => UTF16LE_BOM,
+            Charset::Utf16Be => UTF16BE_BOM,
+            Charset::Cp949 => &[],
+        }
+    }
+}
+
+/**
+ * 디코딩한 파일 내용
+ */
+#[derive(Debug, PartialEq)]
+pub struct Decoded {
+    pub encoding: Encoding,
+    pub text: String,
+    /// 글자로 읽을 수 없어서 `\xNN` 으로 보존한 바이트 수
+    pub escaped: usize,
+    /// 0 바이트가 있거나 보존한 바이트가 많아서 텍스트 파일이 아닌 것 같은지 여부
+    pub binary: bool,
+}
+
+impl Decoded {
+    fn new(encoding: Encoding, (text, escaped): (String, usize), bytes: &[u8]) -> Decoded {
+        let binary = bytes.contains(&0) || escaped * 100 > bytes.len() * BINARY_PERCENT;
+        Decoded {
+            encoding,
+            text,
+            escaped,
+            binary,
+        }
+    }
+}
+
+/**
+파일 내용의 인코딩을 알아내서 디코딩한다.
+BOM, UTF-16 처럼 보이는지(ASCII 글자의 0 바이트), UTF-8 로 읽히는지, CP949 로 읽히는지 순서로 본다.
+어느 것도 아니면 UTF-8 로 읽고, 잘못된 바이트는 `\xNN` 으로 보존해서 저장할 때 그대로 쓴다.
+
+# Arguments
+* `bytes` - 파일 내용
+* `default` - 새 파일의 인코딩 (설정의 file.encoding). 빈 파일, ASCII 만 있는 파일에 쓴다.
+*/
+pub fn decode_auto(bytes: &[u8], default: Encoding) -> Decoded {
+    if bytes.is_empty() {
+        return Decoded::new(default, (String::new(), 0), bytes);
+    }
+
+    if let Some(rest) = bytes.strip_prefix(UTF8_BOM) {
+        return Decoded::new(Encoding::new(Charset::Utf8, true), decode_utf8(rest), bytes);
+    }
+
+    let utf16 = [
+        (UTF16LE_BOM, Charset::Utf16Le, true),
+        (UTF16BE_BOM, Charset::Utf16Be, true),
+    ]
+    .iter()
+    .copied()
+    .find(|(bom, _, _)| bytes.starts_with(bom))
+    .or_else(|| sniff_utf16(bytes).map(|charset| (&[][..], charset, false)));
+    if let Some((bom, charset, has_bom)) = utf16 {
+        if let Some(text) = decode_utf16(&bytes[bom.len()..], charset) {
+            let encoding = Encoding::new(charset, has_bom);
+            return Decoded {
+                encoding,
+                text,
+                escaped: 0,
+                binary: false,
+            };
+        }
+    }
+
+    let valid_utf8 = std::str::from_utf8(bytes).is_ok();
+    if bytes.is_ascii() {
+        let charset = match default.charset {
+            Charset::Utf16Le | Charset::Utf16Be => Charset::Utf8,
+            charset => charset,
+        };
+        return Decoded::new(Encoding::new(charset, false), decode_utf8(bytes), bytes);
+    }
+    if !valid_utf8 && !bytes.contains(&0) {
+        if let Some(text) = EUC_KR.decode_without_bom_handling_and_without_replacement(bytes) {
+            let encoding = Encoding::new(Charset::Cp949, false);
+            return Decoded::new(encoding, escape_controls(&text), bytes);
+        }
+    }
+    Decoded::new(Encoding::UTF8, decode_utf8(bytes), bytes)
+}
+
+/**
+ * BOM 없는 UTF-16. ASCII 글자는 한쪽 바이트가 0 이므로 짝수/홀수 자리의 0 바이트 수로 추측한다.
+ */
+fn sniff_utf16(bytes: &[u8]) -> Option<Charset> {
+    if !bytes.len().is_multiple_of(2) {
+        return None;
+    }
+
+    let head = &bytes[..std::cmp::min(bytes.len(), SNIFF_LEN)];
+    let pairs = head.len() / 2;
+    let zeros = |start: usize| {
+        head.iter()
+            .skip(start)
+            .step_by(2)
+            .filter(|&&b| b == 0)
+            .count()
+    };
+    let (even, odd) = (zeros(0), zeros(1));
+
+    // 0 바이트가 한쪽에만 30% 이상
+    if odd * 10 >= pairs * 3 && even * 10 < pairs {
+        Some(Charset::Utf16Le)
+    } else if even * 10 >= pairs * 3 && odd * 10 < pairs {
+        Some(Charset::Utf16Be)
+    } else {
+        None
+    }
+}
+
+fn decode_utf16(bytes: &[u8], charset: Charset) -> Option<String> {
+    let encoding = match charset {
+        Charset::Utf16Le => UTF_16LE,
+        _ => UTF_16BE,
+    };
+    encoding
+        .decode_without_bom_handling_and_without_replacement(bytes)
+        .map(Cow::into_owned)
+}
+
+/**
+ * UTF-8 로 읽는다. 잘못된 바이트, 제어 문자(탭 제외), 보존용 코드 포인트와 겹치는 글자는 `\xNN` 으로 보존한다.
+ */
+fn decode_utf8(bytes: &[u8]) -> (String, usize) {
+    let mut text = String::with_capacity(bytes.len());
+    let mut escaped = 0;
+    let mut push_escaped = |text: &mut String, bytes: &[u8]| {
+        text.extend(bytes.iter().map(|&b| escape_byte(b)));
+        escaped += bytes.len();
+    };
+
+    for chunk in bytes.utf8_chunks() {
+        for ch in chunk.valid().chars() {
+            if needs_escape(ch) {
+                push_escaped(&mut text, ch.encode_utf8(&mut [0; 4]).as_bytes());
+            } else {
+                text.push(ch);
+            }
+        }
+        push_escaped(&mut text, chunk.invalid());
+    }
+    (text, escaped)
+}
+
+/**
+ * ASCII 와 호환되는 인코딩으로 읽은 내용의 제어 문자를 `\xNN` 으로 바꾼다.
+ */
+fn escape_controls(text: &str) -> (String, usize) {
+    let mut escaped = 0;
+    let text = text
+        .chars()
+        .map(|ch| {
+            if ch.is_ascii() && needs_escape(ch) {
+                escaped += 1;
+                escape_byte(ch as u8)
+            } else {
+                ch
+            }
+        })
+        .collect();
+    (text, escaped)
+}
+
+fn needs_escape(ch: char) -> bool {
+    (ch.is_control() && !matches!(ch, '\t' | '\n' | '\r')) || escaped_byte(ch).is_some()
+}
+
+/**
+ * 지정한 인코딩으로 디코딩한다. (File > Reopen with Encoding)
+ * UTF-8, CP949 는 잘못된 바이트를 `\xNN` 으로 보존하고, UTF-16 은 U+FFFD 로 바꾼다.
+ *
+ * # Return
+ * 디코딩한 내용과, U+FFFD 로 바꿔서 잃은 바이트가 있는지 여부
+ */
+pub fn decode(bytes: &[u8], encoding: Encoding) -> (Decoded, bool) {
+    let body = strip_bom(bytes, encoding.charset);
+    match encoding.charset {
+        Charset::Utf8 => (Decoded::new(encoding, decode_utf8(body), bytes), false),
+        Charset::Cp949 => {
+            let (text, had_errors) = EUC_KR.decode_without_bom_handling(body);
+            (
+                Decoded::new(encoding, escape_controls(&text), bytes),
+                had_errors,
+            )
+        }
+        Charset::Utf16Le | Charset::Utf16Be => {
+            let decoder = if encoding.charset == Charset::Utf16Le {
+                UTF_16LE
+            } else {
+                UTF_16BE
+            };
+            let (text, had_errors) = decoder.decode_without_bom_handling(body);
+            (
+                Decoded::new(encoding, (text.into_owned(), 0), &[]),
+                had_errors,
+            )
+        }
+    }
+}
+
+fn strip_bom(bytes: &[u8], charset: Charset) -> &[u8] {
+    let bom = Encoding::new(charset, true).bom_bytes();
+    bytes.strip_prefix(bom).unwrap_or(bytes)
+}
+
+/**
+지정한 인코딩으로 바꾼다. UTF-8, CP949 에서는 `\xNN` 으로 보존한 바이트를 원래대로 쓴다.
+
+# Return
+* 바꾼 바이트. 그 인코딩으로 나타낼 수 없는 글자가 있으면 그 글자들 (겹치지 않게, 나온 순서대로)
+*/
+pub fn encode(text: &str, encoding: Encoding) -> Result<Vec<u8>, Vec<char>> {
+    let mut bytes = Vec::new();
+    if encoding.bom {
+        bytes.extend_from_slice(encoding.bom_bytes());
+    }
+
+    match encoding.charset {
+        Charset::Utf8 | Charset::Cp949 => {
+            // 보존한 바이트 사이의 글자들을 한꺼번에 바꾼다.
+            let mut start = 0;
+            for (i, ch) in text.char_indices() {
+                if let Some(byte) = escaped_byte(ch) {
+                    encode_run(&text[start..i], encoding.charset, &mut bytes)
+                        .ok_or_else(|| unmappable(text))?;
+                    bytes.push(byte);
+                    start = i + ch.len_utf8();
+                }
+            }
+            encode_run(&text[start..], encoding.charset, &mut bytes)
+                .ok_or_else(|| unmappable(text))?;
+        }
+        Charset::Utf16Le | Charset::Utf16Be => {
+            // UTF-16 에는 따로 떨어진 바이트를 넣을 수 없다.
+            let escapes = unique(text.chars().filter(|&ch| escaped_byte(ch).is_some()));
+            if !escapes.is_empty() {
+                return Err(escapes);
+            }
+            for unit in text.encode_utf16() {
+                if encoding.charset == Charset::Utf16Le {
+                    bytes.extend_from_slice(&unit.to_le_bytes());
+                } else {
+                    bytes.extend_from_slice(&unit.to_be_bytes());
+                }
+            }
+        }
+    }
+    Ok(bytes)
+}
+
+fn encode_run(run: &str, charset: Charset, bytes: &mut Vec<u8>) -> Option<()> {
+    if charset == Charset::Utf8 {
+        bytes.extend_from_slice(run.as_bytes());
+    } else {
+        let (encoded, _, had_errors) = EUC_KR.encode(run);
+        if had_errors {
+            return None;
+        }
+        bytes.extend_from_slice(&encoded);
+    }
+    Some(())
+}
+
+/**
+ * CP949 로 나타낼 수 없는 글자들
+ */
+fn unmappable(text: &str) -> Vec<char> {
+    let mut buf = [0; 4];
+    unique(text.chars().filter(|&ch| {
+        !ch.is_ascii() && escaped_byte(ch).is_none() && EUC_KR.encode(ch.encode_utf8(&mut buf)).2
+    }))
+}
+
+fn unique(chars: impl Iterator<Item = char>) -> Vec<char> {
+    let mut result: Vec<char> = Vec::new();
+    for ch in chars {
+        if !result.contains(&ch) {
+            result.push(ch);
+        }
+    }
+    result
+}
+
+/**
+ * 글자로 읽을 수 없는 바이트를 버퍼에 넣을 때 쓰는 코드 포인트. (보충 사용자 정의 영역의 마지막 256 자)
+ */
+fn escape_byte(byte: u8) -> char {
+    char::from_u32(ESCAPE_BASE + byte as u32).unwrap_or(char::REPLACEMENT_CHARACTER)
+}
+
+/**
+ * `\xNN` 으로 보존한 바이트면 그 바이트
+ */
+pub fn escaped_byte(ch: char) -> Option<u8> {
+    (ch as u32)
+        .checked_sub(ESCAPE_BASE)
+        .and_then(|b| u8::try_from(b).ok())
+}
+
+/**
+ * 화면에 그릴 때의 너비. 보존한 바이트는 `\xNN` 네 칸
+ */
+pub fn char_width(ch: char) -> usize {
+    if escaped_byte(ch).is_some() {
+        4
+    } else {
+        ch.width_cjk().unwrap_or(0)
+    }
+}
+
+pub fn str_width(s: &str) -> usize {
+    s.chars().map(char_width).sum()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn decoded(encoding: Encoding, text: &str) -> Decoded {
+        Decoded {
+            encoding,
+            text: String::from(text),
+            escaped: 0,
+            binary: false,
+        }
+    }
+
+    #[test]
+    fn test_decode_auto() {
+        let utf8 = Encoding::UTF8;
+        let cp949 = Encoding::new(Charset::Cp949, false);
+
+        assert_eq!(decode_auto("한글".as_bytes(), utf8), decoded(utf8, "한글"));
+        assert_eq!(
+            decode_auto(b"\xef\xbb\xbfabc", utf8),
+            decoded(Encoding::new(Charset::Utf8, true), "abc")
+        );
+        // "한글" 의 CP949
+        assert_eq!(
+            decode_auto(b"\xc7\xd1\xb1\xdb\n", utf8),
+            decoded(cp949, "한글\n")
+        );
+        assert_eq!(
+            decode_auto(b"\xff\xfea\x00\x5c\xd5", utf8),
+            decoded(Encoding::new(Charset::Utf16Le, true), "a한")
+        );
+        assert_eq!(
+            decode_auto(b"\x00a\x00b\xd5\x5c\x00\n", utf8),
+            decoded(Encoding::new(Charset::Utf16Be, false), "ab한\n")
+        );
+        // ASCII 만 있으면 설정의 인코딩
+        assert_eq!(decode_auto(b"abc", cp949), decoded(cp949, "abc"));
+    }
+
+    #[test]
+    fn test_escape() {
+        // 잘못된 바이트 몇 개는 보존하고 그대로 저장한다.
+        let bytes = b"caf\xe9 \x1b[0m \xff\xfe\xfd\xfc latin-1 \xe0 text, mostly plain ASCII with a few stray bytes.";
+        let decoded = decode_auto(bytes, Encoding::UTF8);
+        assert_eq!(decoded.encoding, Encoding::UTF8);
+        assert_eq!(decoded.escaped, 7);
+        assert!(!decoded.binary);
+        assert_eq!(str_width(&decoded.text), bytes.len() + 7 * 3);
+        assert_eq!(encode(&decoded.text, decoded.encoding).unwrap(), bytes);
+
+        // 보존용 코드 포인트와 겹치는 글자도 그대로 저장한다.
+        let bytes = "a\u{10ff41}b".as_bytes();
+        let decoded = decode_auto(bytes, Encoding::UTF8);
+        assert_eq!(decoded.escaped, 4);
+        assert_eq!(encode(&decoded.text, decoded.encoding).unwrap(), bytes);
+
+        assert!(decode_auto(b"\x7fELF\x02\x01\x01\x00\x00", Encoding::UTF8).binary);
+        assert!(decode_auto(b"\xff\xd8\xff\xe0\x10JFIF", Encoding::UTF8).binary);
+
+        // CP949 파일 안의 제어 문자
+        let cp949 = Encoding::new(Charset::Cp949, false);
+        let decoded = decode_auto(b"\x1b\xc7\xd1", cp949);
+        assert_eq!(decoded.encoding, cp949);
+        assert_eq!(encode(&decoded.text, cp949).unwrap(), b"\x1b\xc7\xd1");
+
+        let utf16 = Encoding::new(Charset::Utf16Le, true);
+        assert_eq!(encode("a\u{10ff80}", utf16), Err(vec!['\u{10ff80}']));
+    }
+
+    #[test]
+    fn test_encode() {
+        let cp949 = Encoding::new(Charset::Cp949, false);
+        assert_eq!(encode("한글", cp949), Ok(b"\xc7\xd1\xb1\xdb".to_vec()));
+        assert_eq!(encode("가😀나😀", cp949), Err(vec!['😀']));
+
+        let utf16 = Encoding::from_name("UTF-16LE").unwrap();
+        let bytes = encode("a한", utf16).unwrap();
+        assert_eq!(bytes, b"\xff\xfea\x00\x5c\xd5");
+        assert_eq!(decode(&bytes, utf16).0.text, "a한");
+
+        let (decoded, had_errors) = decode(b"\xff\xfea\x00\x00\xd8", utf16);
+        assert_eq!(decoded.text, "a\u{fffd}");
+        assert!(had_errors);
+    }
+}
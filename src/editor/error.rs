@@ -0,0 +1,97 @@
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/**
+ * 실패한 작업의 종류
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Operation {
+    Open,
+    Read,
+    Save,
+    /// 저장하기 전에 원래 파일을 백업
+    Backup,
+    /// 복구용 스왑 파일 쓰기
+    WriteSwap,
+    /// 키보드/마우스 입력 읽기
+    Input,
+    /// 화면 그리기
+    Render,
+}
+
+impl Operation {
+    fn verb(self) -> &'static str {
+        match self {
+            Operation::Open => "open",
+            Operation::Read => "read",
+            Operation::Save => "save",
+            Operation::Backup => "back up",
+            Operation::WriteSwap => "write swap file",
+            Operation::Input => "read input",
+            Operation::Render => "draw the screen",
+        }
+    }
+}
+
+/**
+ * 사용자에게 보여줄 입출력 에러. 어떤 작업에서, 어떤 파일에서 실패했는지 함께 담는다.
+ */
+#[derive(Debug)]
+pub struct EditorError {
+    pub op: Operation,
+    pub path: Option<PathBuf>,
+    pub source: io::Error,
+}
+
+impl EditorError {
+    pub fn new(op: Operation, path: Option<&Path>, source: io::Error) -> EditorError {
+        EditorError {
+            op,
+            path: path.map(Path::to_path_buf),
+            source,
+        }
+    }
+}
+
+impl fmt::Display for EditorError {
+    /**
+     * ex: "Cannot open 'memo.md': Permission denied (os error 13)"
+     */
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.path {
+            Some(path) => write!(
+                f,
+                "Cannot {} '{}': {}",
+                self.op.verb(),
+                path.display(),
+                self.source
+            ),
+            None => write!(f, "Cannot {}: {}", self.op.verb(), self.source),
+        }
+    }
+}
+
+impl std::error::Error for EditorError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let err = EditorError::new(
+            Operation::Save,
+            Some(Path::new("메모.md")),
+            io::Error::new(io::ErrorKind::PermissionDenied, "Permission denied"),
+        );
+        assert_eq!(err.to_string(), "Cannot save '메모.md': Permission denied");
+
+        let err = EditorError::new(Operation::Input, None, io::Error::other("closed"));
+        assert_eq!(err.to_string(), "Cannot read input: closed");
+    }
+}
@@ -0,0 +1,476 @@
+use super::command::{CommandId, CommandRegistry};
+use crossterm::event::{KeyCode, KeyModifiers};
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+
+/**
+ * 키 하나와 수식키 조합 (ex: Ctrl+S)
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct KeyBinding {
+    pub modifiers: KeyModifiers,
+    pub code: KeyCode,
+}
+
+impl KeyBinding {
+    pub fn new(modifiers: KeyModifiers, code: KeyCode) -> KeyBinding {
+        // Shift+글자는 터미널에서 대문자로 들어오므로 설정 파일의 "Shift+a" 도 대문자로 맞추고,
+        // 나머지는 소문자로 맞춰서 "Ctrl+K S" 가 Ctrl+K 다음 s 를 누른 것과 같게 한다.
+        let code = match code {
+            KeyCode::Char(c) if modifiers.contains(KeyModifiers::SHIFT) => {
+                KeyCode::Char(c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => KeyCode::Char(c.to_ascii_lowercase()),
+            _ => code,
+        };
+        KeyBinding { modifiers, code }
+    }
+}
+
+impl fmt::Display for KeyBinding {
+    /**
+     * 메뉴와 설정 파일에서 쓰는 형식 (ex: "Ctrl+S", "Shift+F1")
+     */
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) => write!(f, "{}", c.to_ascii_uppercase()),
+            KeyCode::F(n) => write!(f, "F{}", n),
+            KeyCode::Backspace => write!(f, "Backspace"),
+            KeyCode::Enter => write!(f, "Enter"),
+            KeyCode::Left => write!(f, "Left"),
+            KeyCode::Right => write!(f, "Right"),
+            KeyCode::Up => write!(f, "Up"),
+            KeyCode::Down => write!(f, "Down"),
+            KeyCode::Home => write!(f, "Home"),
+            KeyCode::End => write!(f, "End"),
+            KeyCode::PageUp => write!(f, "PageUp"),
+            KeyCode::PageDown => write!(f, "PageDown"),
+            KeyCode::Tab => write!(f, "Tab"),
+            KeyCode::BackTab => write!(f, "BackTab"),
+            KeyCode::Delete => write!(f, "Delete"),
+            KeyCode::Insert => write!(f, "Insert"),
+            KeyCode::Esc => write!(f, "Esc"),
+            KeyCode::Null => write!(f, "Null"),
+        }
+    }
+}
+
+impl FromStr for KeyBinding {
+    type Err = String;
+
+    /**
+     * "Ctrl+S", "alt+shift+f3", "Space" 같은 문자열을 읽는다. 대소문자는 구분하지 않는다.
+     */
+    fn from_str(s: &str) -> Result<KeyBinding, String> {
+        let parts: Vec<&str> = s.split('+').collect();
+        let (key, modifier_names) = match parts.split_last() {
+            Some((key, modifiers)) if !key.is_empty() => (*key, modifiers),
+            // "Ctrl++" 처럼 '+' 키 자체를 쓰는 경우
+            _ if s.ends_with("++") || s == "+" => ("+", &parts[..parts.len().saturating_sub(2)]),
+            _ => return Err(format!("invalid key '{}'", s)),
+        };
+
+        let mut modifiers = KeyModifiers::NONE;
+        for name in modifier_names {
+            modifiers |= match name.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => KeyModifiers::CONTROL,
+                "alt" | "meta" => KeyModifiers::ALT,
+                "shift" => KeyModifiers::SHIFT,
+                _ => return Err(format!("unknown modifier '{}' in '{}'", name, s)),
+            };
+        }
+
+        let lower = key.to_ascii_lowercase();
+        let code = match lower.as_str() {
+            "space" => KeyCode::Char(' '),
+            "backspace" => KeyCode::Backspace,
+            "enter" | "return" => KeyCode::Enter,
+            "left" => KeyCode::Left,
+            "right" => KeyCode::Right,
+            "up" => KeyCode::Up,
+            "down" => KeyCode::Down,
+            "home" => KeyCode::Home,
+            "end" => KeyCode::End,
+            "pageup" | "pgup" => KeyCode::PageUp,
+            "pagedown" | "pgdn" => KeyCode::PageDown,
+            "tab" => KeyCode::Tab,
+            "backtab" => KeyCode::BackTab,
+            "delete" | "del" => KeyCode::Delete,
+            "insert" | "ins" => KeyCode::Insert,
+            "esc" | "escape" => KeyCode::Esc,
+            _ if lower.len() > 1 && lower.starts_with('f') => match lower[1..].parse::<u8>() {
+                Ok(n) if (1..=24).contains(&n) => KeyCode::F(n),
+                _ => return Err(format!("unknown key '{}' in '{}'", key, s)),
+            },
+            _ => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => KeyCode::Char(c),
+                    _ => return Err(format!("unknown key '{}' in '{}'", key, s)),
+                }
+            }
+        };
+
+        Ok(KeyBinding::new(modifiers, code))
+    }
+}
+
+/**
+ * 키 여러 개를 차례로 누르는 조합 (ex: "Ctrl+K Ctrl+B")
+ */
+pub fn parse_chord(s: &str) -> Result<Vec<KeyBinding>, String> {
+    let keys = s
+        .split_whitespace()
+        .map(KeyBinding::from_str)
+        .collect::<Result<Vec<KeyBinding>, String>>()?;
+
+    if keys.is_empty() {
+        Err(String::from("empty key binding"))
+    } else {
+        Ok(keys)
+    }
+}
+
+pub fn chord_label(keys: &[KeyBinding]) -> String {
+    keys.iter()
+        .map(|key| key.to_string())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/**
+ * 지금까지 누른 키들로 찾은 결과
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum KeyMatch {
+    Command(CommandId),
+    /// 더 눌러야 하는 키 조합의 앞부분
+    Prefix,
+    None,
+}
+
+/**
+ * 키 조합 -> 명령 연결표. 명령의 기본 키 바인딩에 프리셋과 설정 파일의 내용을 덮어쓴다.
+ */
+pub struct Keymap {
+    bindings: HashMap<Vec<KeyBinding>, CommandId>,
+}
+
+impl Keymap {
+    /**
+     * 명령 목록의 기본 키 바인딩으로 만든다.
+     */
+    pub fn new(commands: &CommandRegistry) -> Keymap {
+        let mut bindings = HashMap::new();
+        for command in commands.iter() {
+            for key in &command.keys {
+                bindings.insert(vec![*key], command.id);
+            }
+        }
+
+        Keymap { bindings }
+    }
+
+    /**
+    설정 파일의 [keys] 표로 만든다.
+
+    ```toml
+    [keys]
+    preset = "wordstar"
+
+    [keys.bindings]
+    "Ctrl+K Ctrl+B" = "edit.newline"
+    "Ctrl+S" = ""   # 기본 바인딩 해제
+    ```
+
+    # Return
+    * (키 연결표, 알 수 없는 명령/키/겹치는 바인딩 등 사용자에게 알릴 문제 목록)
+    */
+    pub fn from_config(
+        commands: &CommandRegistry,
+        table: Option<&toml::value::Table>,
+    ) -> (Keymap, Vec<String>) {
+        let mut keymap = Keymap::new(commands);
+        let mut problems = Vec::new();
+
+        let table = match table {
+            Some(table) => table,
+            None => return (keymap, problems),
+        };
+
+        match table.get("preset") {
+            None => {}
+            Some(toml::Value::String(name)) => {
+                if let Err(e) = keymap.apply_preset(commands, name) {
+                    problems.push(e);
+                }
+            }
+            Some(_) => problems.push(String::from("keys.preset must be a string")),
+        }
+
+        match table.get("bindings") {
+            None => {}
+            Some(toml::Value::Table(bindings)) => {
+                let mut seen: HashMap<Vec<KeyBinding>, &str> = HashMap::new();
+                for (chord, value) in bindings {
+                    let keys = match parse_chord(chord) {
+                        Ok(keys) => keys,
+                        Err(e) => {
+                            problems.push(format!("keys.bindings: {}", e));
+                            continue;
+                        }
+                    };
+
+                    // "ctrl+s" 와 "Ctrl+S" 는 TOML 에서는 다른 키지만 같은 키 조합이다.
+                    if let Some(prev) = seen.insert(keys.clone(), chord) {
+                        problems.push(format!(
+                            "keys.bindings: '{}' and '{}' are the same key",
+                            prev, chord
+                        ));
+                    }
+
+                    match value.as_str() {
+                        Some("") => {
+                            keymap.bindings.remove(&keys);
+                        }
+                        Some(name) => match commands.get(name) {
+                            Some(command) => {
+                                keymap.bindings.insert(keys, command.id);
+                            }
+                            None => problems.push(format!(
+                                "keys.bindings: unknown command '{}' for {}",
+                                name, chord
+                            )),
+                        },
+                        None => problems.push(format!(
+                            "keys.bindings: command for {} must be a string",
+                            chord
+                        )),
+                    }
+                }
+            }
+            Some(_) => problems.push(String::from("keys.bindings must be a table")),
+        }
+
+        problems.extend(keymap.conflicts());
+        (keymap, problems)
+    }
+
+    /**
+     * 미리 정해둔 키 배치를 덮어쓴다. 지금은 "default" 와 "wordstar" 가 있다.
+     */
+    pub fn apply_preset(&mut self, commands: &CommandRegistry, name: &str) -> Result<(), String> {
+        let preset: &[(&str, CommandId)] = match name.to_ascii_lowercase().as_str() {
+            "default" => &[],
+            "wordstar" => WORDSTAR_PRESET,
+            _ => return Err(format!("unknown key preset '{}'", name)),
+        };
+
+        for (chord, id) in preset {
+            let keys = parse_chord(chord)?;
+            match commands.get(id) {
+                Some(command) => {
+                    self.bindings.insert(keys, command.id);
+                }
+                None => return Err(format!("preset {}: unknown command '{}'", name, id)),
+            }
+        }
+
+        Ok(())
+    }
+
+    /**
+     * 다른 키 조합의 앞부분과 같아서 절대 실행될 수 없는 바인딩 목록
+     */
+    fn conflicts(&self) -> Vec<String> {
+        let mut problems: Vec<String> = self
+            .bindings
+            .iter()
+            .filter(|(keys, _)| keys.len() > 1)
+            .filter_map(|(keys, id)| {
+                (1..keys.len())
+                    .find_map(|n| {
+                        self.bindings
+                            .get(&keys[..n])
+                            .map(|prefix_id| (n, prefix_id))
+                    })
+                    .map(|(n, prefix_id)| {
+                        format!(
+                            "keys: {} ({}) hides {} ({})",
+                            chord_label(&keys[..n]),
+                            prefix_id,
+                            chord_label(keys),
+                            id
+                        )
+                    })
+            })
+            .collect();
+
+        problems.sort();
+        problems
+    }
+
+    /**
+     * 지금까지 누른 키들에 해당하는 명령을 찾는다.
+     */
+    pub fn lookup(&self, keys: &[KeyBinding]) -> KeyMatch {
+        if let Some(id) = self.bindings.get(keys) {
+            return KeyMatch::Command(id);
+        }
+
+        if self
+            .bindings
+            .keys()
+            .any(|chord| chord.len() > keys.len() && chord.starts_with(keys))
+        {
+            KeyMatch::Prefix
+        } else {
+            KeyMatch::None
+        }
+    }
+
+    /**
+     * 메뉴에 표시할 단축키. 여러 개면 가장 짧은 것.
+     */
+    pub fn shortcut_label(&self, id: &str) -> Option<String> {
+        self.bindings
+            .iter()
+            .filter(|(_, command_id)| **command_id == id)
+            .map(|(keys, _)| chord_label(keys))
+            .min_by(|a, b| a.len().cmp(&b.len()).then(a.cmp(b)))
+    }
+}
+
+/// WordStar 계열(MS-DOS EDIT 도 일부 지원) 키 배치
+const WORDSTAR_PRESET: &[(&str, CommandId)] = &[
+    ("Ctrl+E", "cursor.up"),
+    ("Ctrl+X", "cursor.down"),
+    ("Ctrl+S", "cursor.left"),
+    ("Ctrl+D", "cursor.right"),
+    ("Ctrl+H", "edit.backspace"),
+    ("Ctrl+V", "edit.toggle_overwrite"),
+    ("Ctrl+K Ctrl+S", "file.save"),
+    ("Ctrl+K S", "file.save"),
+    ("Ctrl+K Ctrl+Q", "file.exit"),
+    ("Ctrl+K Q", "file.exit"),
+];
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn key(s: &str) -> KeyBinding {
+        KeyBinding::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_key_label() {
+        let k = KeyBinding::new(KeyModifiers::CONTROL, KeyCode::Char('s'));
+        assert_eq!(k.to_string(), "Ctrl+S");
+
+        let k = KeyBinding::new(KeyModifiers::SHIFT | KeyModifiers::ALT, KeyCode::F(3));
+        assert_eq!(k.to_string(), "Alt+Shift+F3");
+    }
+
+    #[test]
+    fn test_parse_key() {
+        assert_eq!(
+            key("ctrl+s"),
+            KeyBinding::new(KeyModifiers::CONTROL, KeyCode::Char('s'))
+        );
+        assert_eq!(key("Ctrl+S"), key("ctrl+s"));
+        assert_eq!(
+            key("Shift+a"),
+            KeyBinding::new(KeyModifiers::SHIFT, KeyCode::Char('A'))
+        );
+        assert_eq!(
+            key("F10"),
+            KeyBinding::new(KeyModifiers::NONE, KeyCode::F(10))
+        );
+        assert_eq!(
+            key("f"),
+            KeyBinding::new(KeyModifiers::NONE, KeyCode::Char('f'))
+        );
+        assert_eq!(
+            key("Ctrl++"),
+            KeyBinding::new(KeyModifiers::CONTROL, KeyCode::Char('+'))
+        );
+        assert!(KeyBinding::from_str("Hyper+S").is_err());
+        assert!(KeyBinding::from_str("Ctrl+Foo").is_err());
+        assert!(KeyBinding::from_str("F99").is_err());
+
+        assert_eq!(
+            parse_chord("Ctrl+K  Ctrl+B").unwrap(),
+            vec![key("Ctrl+K"), key("Ctrl+B")]
+        );
+        assert!(parse_chord("  ").is_err());
+        assert_eq!(chord_label(&parse_chord("ctrl+k b").unwrap()), "Ctrl+K B");
+    }
+
+    #[test]
+    fn test_lookup() {
+        let commands = CommandRegistry::new();
+        let mut keymap = Keymap::new(&commands);
+        keymap.apply_preset(&commands, "wordstar").unwrap();
+
+        assert_eq!(
+            keymap.lookup(&[key("Ctrl+S")]),
+            KeyMatch::Command("cursor.left")
+        );
+        assert_eq!(keymap.lookup(&[key("Ctrl+K")]), KeyMatch::Prefix);
+        assert_eq!(
+            keymap.lookup(&[key("Ctrl+K"), key("S")]),
+            KeyMatch::Command("file.save")
+        );
+        assert_eq!(keymap.lookup(&[key("Ctrl+K"), key("Z")]), KeyMatch::None);
+        assert_eq!(
+            keymap.shortcut_label("file.save"),
+            Some(String::from("Ctrl+K S"))
+        );
+        assert!(keymap.apply_preset(&commands, "emacs").is_err());
+    }
+
+    #[test]
+    fn test_from_config() {
+        let commands = CommandRegistry::new();
+        let config: toml::Value = toml::from_str(
+            r#"
+            preset = "wordstar"
+
+            [bindings]
+            "Ctrl+Q" = "cursor.up"
+            "ctrl+q" = "cursor.down"
+            "Ctrl+K Ctrl+B" = "no.such.command"
+            "Ctrl+K" = "help.about"
+            "F10" = ""
+            "Hyper+X" = "file.exit"
+            "#,
+        )
+        .unwrap();
+
+        let (keymap, problems) = Keymap::from_config(&commands, config.as_table());
+        assert_eq!(keymap.lookup(&[key("F10")]), KeyMatch::None);
+        assert_eq!(
+            keymap.lookup(&[key("Ctrl+K")]),
+            KeyMatch::Command("help.about")
+        );
+
+        let has = |s: &str| problems.iter().any(|p| p.contains(s));
+        assert!(has("are the same key"));
+        assert!(has("unknown command 'no.such.command'"));
+        assert!(has("Hyper"));
+        assert!(has("Ctrl+K (help.about) hides Ctrl+K S (file.save)"));
+    }
+}
@@ -1,21 +1,25 @@
+use crate::check_result;
+use crate::editor::encoding::{char_width, escaped_byte, str_width};
+use crate::editor::line_ending::LineEnding;
+use crate::editor::ui::theme::ColorPair;
+use crate::editor::util::set_color;
+use crossterm::queue;
+use crossterm::style::{Attribute, ResetColor, SetAttribute};
+use log::error;
 use std::convert::TryInto;
 use std::{cmp, fmt};
-use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
-#[derive(Debug)]
+#[derive(Debug, PartialEq)]
 pub enum LineErr {
     EndOfString,
 }
 
-impl std::cmp::PartialEq for LineErr {
-    fn eq(&self, other: &LineErr) -> bool {
-        self == other
-    }
-}
-
+#[derive(Clone)]
 pub struct LineBuffer {
     s: String,
     byte_index: usize,
+    /// 이 줄 뒤에 쓰는 줄 끝 문자. 파일의 마지막 줄이면 final newline 설정을 따른다.
+    ending: LineEnding,
 }
 
 impl LineBuffer {
@@ -27,6 +31,7 @@ impl LineBuffer {
         LineBuffer {
             s: String::new(),
             byte_index: 0,
+            ending: LineEnding::native(),
         }
     }
 
@@ -34,18 +39,57 @@ impl LineBuffer {
         LineBuffer {
             s: String::from(arg),
             byte_index: 0,
+            ending: LineEnding::native(),
         }
     }
 
+    pub fn with_ending(arg: &str, ending: LineEnding) -> LineBuffer {
+        let mut line = LineBuffer::from(arg);
+        line.ending = ending;
+        line
+    }
+
     /*
     Immutable functions
     */
 
-    pub fn draw(&self, screen_width: usize) {
-        print!("{}", self.s);
+    pub fn draw(&self, screen_width: usize, colors: ColorPair) {
+        set_color(colors.fg, colors.bg);
+
+        // 글자로 읽을 수 없어서 보존한 바이트는 반전된 \xNN 으로 그린다.
+        // 너비를 넘는 글자는 그리지 않는다. (창을 나누면 옆 창을 덮으므로)
+        let limit = screen_width.saturating_sub(1);
+        let mut start = 0;
+        let mut end = self.s.len();
+        let mut used = 0;
+        for (i, ch) in self.s.char_indices() {
+            let width = char_width(ch);
+            if used + width > limit {
+                end = i;
+                break;
+            }
+            used += width;
+            if let Some(byte) = escaped_byte(ch) {
+                print!("{}", &self.s[start..i]);
+                check_result!(
+                    queue!(std::io::stdout(), SetAttribute(Attribute::Reverse)),
+                    "LineBuffer::draw"
+                );
+                print!("\\x{:02X}", byte);
+                check_result!(
+                    queue!(std::io::stdout(), SetAttribute(Attribute::NoReverse)),
+                    "LineBuffer::draw"
+                );
+                start = i + ch.len_utf8();
+            }
+        }
+        print!("{}", &self.s[start..end]);
+
+        // 배경색이 줄 끝까지 보이도록 공백으로 채운다.
+        print!("{}", " ".repeat(limit - used));
 
-        for _ in self.s.len()..screen_width - 1 {
-            print!(" ");
+        if let Err(e) = queue!(std::io::stdout(), ResetColor) {
+            error!("LineBuffer::draw: {}", e);
         }
     }
 
@@ -56,7 +100,7 @@ impl LineBuffer {
 
     #[cfg(test)]
     pub fn width(&self) -> usize {
-        self.s.width_cjk()
+        str_width(&self.s)
     }
 
     pub fn current_char(&self) -> char {
@@ -87,18 +131,38 @@ impl LineBuffer {
         if self.current_char() == '\0' {
             0
         } else {
-            self.current_char().width_cjk().unwrap_or(0)
+            char_width(self.current_char())
         }
     }
 
+    /**
+        현재 커서 앞에 있는 글자 수. (바이트 단위가 아님)
+    */
+    pub fn char_index(&self) -> usize {
+        let mut index = cmp::min(self.byte_index, self.s.len());
+        while !self.s.is_char_boundary(index) {
+            index -= 1;
+        }
+
+        self.s[..index].chars().count()
+    }
+
     pub fn get_buffer(&self) -> &String {
         &self.s
     }
 
+    pub fn ending(&self) -> LineEnding {
+        self.ending
+    }
+
     /*
     Mutable functions
     */
 
+    pub fn set_ending(&mut self, ending: LineEnding) {
+        self.ending = ending;
+    }
+
     /* note: 비활성화한 테스트에서 사용
     #[cfg(test)]
     pub fn push_str(&mut self, s: &str) {
@@ -115,6 +179,30 @@ impl LineBuffer {
         self.byte_index += ch.len_utf8();
     }
 
+    /**
+        현재 byte_index 위치의 글자를 덮어쓴다. (겹쳐쓰기 모드)
+        뒤쪽 글자의 화면 위치가 어긋나지 않도록 새 글자의 너비만큼 기존 글자를 지우고,
+        지운 너비가 더 넓으면(전각 글자를 반각 글자로 덮은 경우) 남는 칸을 공백으로 채운다.
+    */
+    pub fn overwrite(&mut self, ch: char) {
+        self.byte_index = cmp::min(self.byte_index, self.s.len());
+        while !self.s.is_char_boundary(self.byte_index) {
+            self.byte_index -= 1;
+        }
+
+        let new_width = char_width(ch);
+        let mut removed_width = 0;
+        while removed_width < new_width && self.byte_index < self.s.len() {
+            let removed = self.s.remove(self.byte_index);
+            removed_width += char_width(removed);
+        }
+
+        self.insert(ch);
+        for _ in new_width..removed_width {
+            self.s.insert(self.byte_index, ' ');
+        }
+    }
+
     /**
         지정한 위치의 이전 글자를 삭제한다. (지정한 위치가 아님)
     */
@@ -178,7 +266,7 @@ impl LineBuffer {
             }
         }
 
-        let head_width = self.s[..self.byte_index].width_cjk();
+        let head_width = str_width(&self.s[..self.byte_index]);
         (self.byte_index as u16, head_width as u16)
     }
 
@@ -197,7 +285,7 @@ impl LineBuffer {
         let mut prev_c_len: usize = 0;
 
         for c in self.s.chars() {
-            let c_width = c.width_cjk().unwrap_or(0) as i32;
+            let c_width = char_width(c) as i32;
 
             match x {
                 0 => break,
@@ -215,7 +303,7 @@ impl LineBuffer {
 
         let new_cursor_x: u16 = std::cmp::min(
             (cursor_x + x).try_into().unwrap_or(0),
-            self.s.width_cjk().try_into().unwrap_or(0),
+            str_width(&self.s).try_into().unwrap_or(0),
         );
 
         (new_cursor_x, byte_index as u16)
@@ -311,6 +399,48 @@ mod test {
         assert_eq!(s2.set_byte_index(3), (3, 3));
     }
 
+    #[test]
+    fn test_overwrite() {
+        let mut s: LineBuffer = LineBuffer::from("abcd");
+        s.overwrite('x');
+        assert_eq!(s.s, "xbcd");
+        assert_eq!(s.byte_index, 1);
+
+        // 반각 두 글자를 전각 한 글자로
+        s.overwrite('가');
+        assert_eq!(s.s, "x가d");
+        assert_eq!(s.byte_index, 4);
+
+        // 줄 끝에서는 삽입과 같다
+        s.byte_index = s.len();
+        s.overwrite('e');
+        assert_eq!(s.s, "x가de");
+
+        // 전각 글자를 반각 글자로 덮으면 남는 한 칸은 공백
+        s.byte_index = 1;
+        s.overwrite('y');
+        assert_eq!(s.s, "xy de");
+        assert_eq!(s.byte_index, 2);
+
+        // 전각 글자를 전각 글자로
+        let mut s2: LineBuffer = LineBuffer::from("가나");
+        s2.overwrite('다');
+        assert_eq!(s2.s, "다나");
+    }
+
+    #[test]
+    fn test_char_index() {
+        let mut s: LineBuffer = LineBuffer::from("가b다");
+        assert_eq!(s.char_index(), 0);
+
+        s.next().unwrap();
+        s.next().unwrap();
+        assert_eq!(s.char_index(), 2);
+
+        s.byte_index = 5;
+        assert_eq!(s.char_index(), 2);
+    }
+
     #[test]
     fn test_width_conv() {
         // unicode-width 를 bytes-length 로 변환하는 코드 테스트.
@@ -0,0 +1,155 @@
+/**
+ * 줄 끝 문자. 파일을 열 때 줄마다 알아내고, 저장할 때 그대로 다시 쓴다.
+ */
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LineEnding {
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const ALL: [LineEnding; 3] = [LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr];
+
+    /**
+     * 새 파일에 쓰는 줄 끝 문자. Windows 는 CRLF, 나머지는 LF
+     */
+    pub fn native() -> LineEnding {
+        if cfg!(windows) {
+            LineEnding::CrLf
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    /**
+     * 상태 표시줄에 보여주는 이름
+     */
+    pub fn label(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        }
+    }
+}
+
+/**
+ * 파일 내용을 나눈 결과
+ */
+#[derive(Debug, PartialEq)]
+pub struct SplitText<'a> {
+    /// 줄 내용과 그 줄의 줄 끝 문자. 마지막 줄 뒤에 줄 끝 문자가 없으면 None
+    pub lines: Vec<(&'a str, Option<LineEnding>)>,
+}
+
+impl<'a> SplitText<'a> {
+    /**
+     * 마지막 줄이 줄 끝 문자로 끝나는지 여부
+     */
+    pub fn final_newline(&self) -> bool {
+        matches!(self.lines.last(), Some((_, Some(_))))
+    }
+
+    /**
+     * 가장 많이 쓴 줄 끝 문자. 새로 만드는 줄에 쓴다. 줄 끝 문자가 하나도 없으면 None
+     */
+    pub fn dominant(&self) -> Option<LineEnding> {
+        let count = |ending| {
+            self.lines
+                .iter()
+                .filter(|(_, e)| *e == Some(ending))
+                .count()
+        };
+        // 개수가 같으면 앞의 것 (LF, CRLF, CR 순서). max_by_key 는 같으면 뒤의 것을 고르므로 거꾸로 센다.
+        LineEnding::ALL
+            .iter()
+            .rev()
+            .map(|&ending| (ending, count(ending)))
+            .filter(|(_, n)| *n > 0)
+            .max_by_key(|(_, n)| *n)
+            .map(|(ending, _)| ending)
+    }
+}
+
+/**
+`\n`, `\r\n`, `\r` 를 모두 줄 끝으로 보고 나눈다. 한 파일 안에서 섞여 있어도 된다.
+파일이 줄 끝 문자로 끝나면 빈 줄을 하나 더 만들지 않는다. 빈 내용은 빈 줄 하나가 된다.
+
+# Arguments
+* `text` - 파일 내용
+*/
+pub fn split_lines(text: &str) -> SplitText<'_> {
+    let bytes = text.as_bytes();
+    let mut lines = Vec::new();
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        let (ending, len) = match bytes[i] {
+            b'\n' => (LineEnding::Lf, 1),
+            b'\r' if bytes.get(i + 1) == Some(&b'\n') => (LineEnding::CrLf, 2),
+            b'\r' => (LineEnding::Cr, 1),
+            _ => {
+                i += 1;
+                continue;
+            }
+        };
+        lines.push((&text[start..i], Some(ending)));
+        i += len;
+        start = i;
+    }
+
+    if start < text.len() || lines.is_empty() {
+        lines.push((&text[start..], None));
+    }
+
+    SplitText { lines }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_split_lines() {
+        use LineEnding::*;
+
+        let text = split_lines("a\r\nb\nc\rd");
+        assert_eq!(
+            text.lines,
+            vec![
+                ("a", Some(CrLf)),
+                ("b", Some(Lf)),
+                ("c", Some(Cr)),
+                ("d", None)
+            ]
+        );
+        assert!(!text.final_newline());
+
+        let text = split_lines("a\r\n\r\n");
+        assert_eq!(text.lines, vec![("a", Some(CrLf)), ("", Some(CrLf))]);
+        assert!(text.final_newline());
+        assert_eq!(text.dominant(), Some(CrLf));
+
+        let text = split_lines("");
+        assert_eq!(text.lines, vec![("", None)]);
+        assert_eq!(text.dominant(), None);
+
+        // \r 바로 뒤의 \n 만 CRLF 로 본다
+        assert_eq!(
+            split_lines("\r\r\n").lines,
+            vec![("", Some(Cr)), ("", Some(CrLf))]
+        );
+        // 개수가 같으면 LF
+        assert_eq!(split_lines("a\r\nb\n").dominant(), Some(Lf));
+    }
+}
@@ -1,32 +1,98 @@
+mod command;
 mod cursor;
+mod display_dialog;
+mod document;
+pub mod encoding;
+mod error;
+mod keymap;
 mod line_buffer;
-mod simple_dialog;
+mod line_ending;
+mod pane;
+mod save;
+mod swap;
+mod undo;
 mod util;
 mod ui {
+    pub mod dialog;
+    pub mod gutter;
+    pub mod hex_view;
     pub mod menu_bar;
     pub mod rect;
+    pub mod split;
+    pub mod status_bar;
+    pub mod theme;
 }
 
 use crate::check_result;
+use crate::config::{self, Config, LineNumbers};
 use crate::consts::ui::MenuCmd;
+use command::{CommandId, CommandRegistry};
 use cursor::Cursor;
+use display_dialog::DisplayOptions;
+use document::Document;
+use encoding::{Charset, Decoded, Encoding};
+use error::{EditorError, Operation};
+use keymap::{chord_label, KeyBinding, KeyMatch, Keymap};
 use line_buffer::LineBuffer;
+use line_ending::LineEnding;
 use log::{error, info};
+use pane::Pane;
 use queues::*;
-use simple_dialog::SimpleDialog;
+use std::collections::{HashMap, VecDeque};
 use std::env;
-use std::fs::File;
-use std::io::{self, BufRead, Stdout, Write};
-use ui::menu_bar::MenuBar;
-use unicode_width::UnicodeWidthChar;
+use std::fs;
+use std::io::{self, Stdout, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use ui::dialog::{
+    message_box, Button, Buttons, Dialog, DialogEvent, DialogResult, Label, Severity, TextInput,
+};
+use ui::gutter::{Gutter, GutterMode};
+use ui::hex_view::HexView;
+use ui::menu_bar::{ItemState, MenuBar};
+use ui::split::{Area, Layout, SplitDirection};
+use ui::status_bar::{StatusBar, StatusInfo};
+use ui::theme::{detect_color_depth, markdown_line_kind, ColorPair, Theme, TokenKind};
+use util::set_color;
+
+#[cfg(unix)]
+use signal_hook::consts::{SIGHUP, SIGTERM};
 
 use crossterm::{
-    event::{self, Event, KeyCode, KeyEvent, KeyModifiers},
+    cursor::{CursorShape, SetCursorShape},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyEvent, KeyModifiers,
+        MouseButton, MouseEvent, MouseEventKind,
+    },
     execute, queue,
+    style::ResetColor,
     terminal::{self, size, Clear, ClearType},
     Result,
 };
 
+/// 마우스 휠 한 칸에 스크롤할 줄 수
+const MOUSE_WHEEL_LINES: i32 = 3;
+/// 상태 표시줄 알림을 보여주는 시간
+const STATUS_MESSAGE_DURATION: Duration = Duration::from_secs(5);
+/// 바뀐 내용을 스왑 파일에 쓰는 최소 간격
+const SWAP_INTERVAL: Duration = Duration::from_secs(4);
+/// 남아 있는 스왑 파일을 어떻게 할지 묻는 버튼
+const SWAP_BUTTONS: &[(&str, &str)] = &[
+    ("recover", "&Recover"),
+    ("delete", "&Delete"),
+    ("read_only", "Open &Read-only"),
+];
+/// 이름 없는 파일의 스왑 파일은 읽기 전용으로 열 파일이 없다.
+const UNTITLED_SWAP_BUTTONS: &[(&str, &str)] = &[
+    ("recover", "&Recover"),
+    ("delete", "&Delete"),
+    ("ignore", "&Ignore"),
+];
+/// 텍스트 파일이 아닌 것 같은 파일을 열지 묻는 버튼
+const BINARY_BUTTONS: &[(&str, &str)] = &[("open", "&Open"), ("cancel", "&Cancel")];
+
 enum RefreshOption {
     None,
     Line,
@@ -35,36 +101,535 @@ enum RefreshOption {
 
 pub struct Editor {
     screen: Stdout,
-    contents: Vec<LineBuffer>,
-    cursor: Cursor,
-    popup: Option<SimpleDialog>,
+    /// 열려 있는 문서들. 하나도 없으면 이름 없는 빈 문서를 하나 만든다.
+    documents: Vec<Document>,
+    /// 편집 중인 문서의 인덱스. 커서가 있는 창이 보여주는 문서
+    current: usize,
+    /// 편집 영역을 나눈 창들
+    panes: Vec<Pane>,
+    /// 창 배치
+    layout: Layout,
+    /// 커서가 있는 창의 인덱스
+    focus: usize,
+    /// 떠 있는 대화상자 (ex: 메시지 상자, Options > Display)
+    dialog: Option<Dialog>,
+    /// 대화상자가 떠 있는 동안 생긴 메시지 상자. 앞의 대화상자를 닫으면 차례로 띄운다.
+    dialog_queue: VecDeque<Dialog>,
+    /// 상태 표시줄에 잠깐 보여주는 알림과 보여주기 시작한 시각
+    status_message: Option<(String, Instant)>,
     menu_bar: MenuBar,
+    gutter: Gutter,
+    commands: CommandRegistry,
+    keymap: Keymap,
+    /// 여러 키로 된 키 조합(ex: Ctrl+K Ctrl+S)을 입력하는 중일 때 지금까지 누른 키들
+    pending_keys: Vec<KeyBinding>,
     cmd_queue: Queue<MenuCmd>,
+    config: Config,
+    theme: Theme,
+    /// 덮어쓸지 묻고 있는 Save As 파일 이름
+    pending_save_as: Option<String>,
+    /// 바뀐 내용을 버릴지 묻고 있는 Reopen with Encoding 의 문자 집합
+    pending_reopen: Option<Charset>,
+    /// 종료하기 전에 저장하려고 Save As 를 띄웠으면 저장한 다음 종료한다.
+    exit_after_save: bool,
+    /// 문서를 닫기 전에 저장하려고 Save As 를 띄웠으면 저장한 다음 닫는다.
+    close_after_save: bool,
+    /// 겹쳐쓰기 모드 (Insert 키로 전환)
+    overwrite: bool,
+    debug: bool,
+    /// 받은 SIGHUP/SIGTERM 번호. 받지 않았으면 0
+    terminate_signal: Arc<AtomicUsize>,
 }
 
 impl Editor {
-    // 기본값
-    pub fn new() -> Editor {
+    /**
+    # Arguments
+    * `config` - 사용자 설정
+    * `problems` - 설정 파일을 읽으면서 생긴 문제. 첫 화면에 알린다.
+    */
+    pub fn new(config: Config, mut problems: Vec<String>) -> Editor {
         info!("Create new editor object");
 
+        let commands = CommandRegistry::new();
+        let keymap = Keymap::new(&commands);
+
         let mut ed = Editor {
             screen: std::io::stdout(),
-            cursor: Cursor::new(),
-            contents: Vec::from([LineBuffer::new()]),
-            popup: None,
-            menu_bar: MenuBar::new(),
+            documents: Vec::new(),
+            current: 0,
+            panes: Vec::from([Pane {
+                document: 0,
+                cursor: Cursor::new(),
+                hex_top: 0,
+            }]),
+            layout: Layout::Pane(0),
+            focus: 0,
+            dialog: None,
+            dialog_queue: VecDeque::new(),
+            status_message: None,
+            menu_bar: MenuBar::new(&commands, &keymap),
+            gutter: Gutter::new(),
+            commands,
+            keymap,
+            pending_keys: Vec::new(),
             cmd_queue: Queue::new(),
+            config: Config::default(),
+            theme: Theme::default(),
+            pending_save_as: None,
+            pending_reopen: None,
+            exit_after_save: false,
+            close_after_save: false,
+            overwrite: false,
+            debug: false,
+            terminate_signal: Arc::new(AtomicUsize::new(0)),
         };
+        problems.extend(ed.apply_config(config));
+        ed.documents.push(Document::new(
+            ed.config.encoding,
+            Some(swap::swap_path(None)),
+        ));
+
+        // --debug 옵션을 주면 상태 표시줄 위에 디버그 정보를 출력한다.
+        let (flags, args): (Vec<String>, Vec<String>) =
+            env::args().skip(1).partition(|arg| arg.starts_with("--"));
+        ed.debug = flags.iter().any(|flag| flag == "--debug");
 
-        let args: Vec<String> = env::args().collect();
-        if args.len() == 2 {
-            info!("Open file {:?}", args[1]);
-            ed.open_file(&args[1])
+        ed.show_config_problems(&problems);
+
+        if !args.is_empty() {
+            for (i, filename) in args.iter().enumerate() {
+                info!("Open file {:?}", filename);
+                if ed.find_document(filename).is_some() {
+                    continue;
+                }
+                if i > 0 {
+                    ed.documents.push(Document::new(ed.config.encoding, None));
+                    ed.current = ed.documents.len() - 1;
+                }
+                ed.open_file(filename);
+            }
+            ed.current = 0;
+        } else if let Some(path) = swap::find_stale_untitled() {
+            ed.check_stale_swap(path);
         }
 
         ed
     }
 
+    /**
+    설정을 에디터에 적용한다. 키 연결표, 메뉴의 단축키, 색, 줄 번호를 다시 만든다.
+
+    # Return
+    * 키 바인딩, 색 설정에서 생긴 문제 목록
+    */
+    fn apply_config(&mut self, config: Config) -> Vec<String> {
+        let (keymap, mut problems) = Keymap::from_config(&self.commands, config.keys.as_ref());
+        let depth = config.color_depth.unwrap_or_else(detect_color_depth);
+        let (theme, theme_problems) = Theme::build(&config.theme, &config.colors, depth);
+        problems.extend(theme_problems);
+
+        self.keymap = keymap;
+        self.pending_keys.clear();
+        self.menu_bar = MenuBar::new(&self.commands, &self.keymap);
+        self.theme = theme;
+        self.gutter.mode = match config.line_numbers {
+            LineNumbers::Off => GutterMode::Off,
+            LineNumbers::Absolute => GutterMode::Absolute,
+            LineNumbers::Relative => GutterMode::Relative,
+        };
+        self.config = config;
+
+        problems
+    }
+
+    /**
+     * 테마를 화면에 적용한다. 설정 파일의 [colors] 는 새 테마 위에 다시 덮어쓴다.
+     * 설정의 테마 이름은 바꾸지 않으므로 미리 보기에도 쓴다.
+     */
+    fn set_theme(&mut self, name: &str) {
+        let depth = self.config.color_depth.unwrap_or_else(detect_color_depth);
+        let (theme, problems) = Theme::build(name, &self.config.colors, depth);
+        for problem in problems {
+            error!("set_theme: {}", problem);
+        }
+
+        self.theme = theme;
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 대화상자를 화면 가운데에 띄운다. 이미 떠 있는 대화상자는 닫는다. 화면은 다시 그리지 않는다.
+     */
+    fn open_dialog(&mut self, mut dialog: Dialog) {
+        dialog.resize(screen_width() as u16, screen_height());
+        self.dialog = Some(dialog);
+    }
+
+    /**
+     * 메시지 상자를 띄운다. 누른 버튼은 handle_dialog_event 에서 id 로 구분해서 처리한다.
+     * 다른 대화상자가 떠 있으면 그 대화상자를 닫은 다음에 띄운다.
+     */
+    fn show_message(&mut self, id: &'static str, severity: Severity, msg: &str, buttons: Buttons) {
+        let dialog = message_box(id, severity, msg, buttons, screen_width() as u16);
+        if self.dialog.is_some() {
+            self.dialog_queue.push_back(dialog);
+        } else {
+            self.open_dialog(dialog);
+        }
+    }
+
+    /**
+     * 상태 표시줄에 알림을 잠깐 보여준다. 화면은 다음 refresh 때 그려진다.
+     */
+    fn notify(&mut self, msg: String) {
+        info!("notify: {}", msg);
+        self.status_message = Some((msg, Instant::now()));
+    }
+
+    /**
+     * 작업을 계속할 수 없는 에러를 로그에 남기고 에러 상자로 알린다.
+     */
+    fn report_error(&mut self, err: EditorError) {
+        error!("{}", err);
+        self.show_message("error", Severity::Error, &err.to_string(), Buttons::Ok);
+    }
+
+    /**
+     * 입력이 없을 때 주기적으로 불린다. 시간이 지난 알림을 지운다.
+     */
+    fn tick(&mut self) {
+        let expired = match &self.status_message {
+            Some((_, since)) => since.elapsed() >= STATUS_MESSAGE_DURATION,
+            None => false,
+        };
+        if expired {
+            self.status_message = None;
+            self.refresh(RefreshOption::None);
+        }
+    }
+
+    fn mark_modified(&mut self) {
+        let doc = self.doc_mut();
+        doc.modified = true;
+        doc.swap_dirty = true;
+    }
+
+    /**
+     * 마지막 편집을 되돌린다. (Edit > Undo)
+     */
+    fn undo(&mut self) {
+        let cursor = self.doc_mut().undo();
+        self.restore(cursor);
+    }
+
+    /**
+     * 되돌린 편집을 다시 한다. (Edit > Redo)
+     */
+    fn redo(&mut self) {
+        let cursor = self.doc_mut().redo();
+        self.restore(cursor);
+    }
+
+    /**
+     * 되돌리거나 다시 한 뒤에 커서를 옮긴다. 저장한 상태로 돌아왔으면 바뀐 것이 없다.
+     */
+    fn restore(&mut self, cursor: Option<Cursor>) {
+        let cursor = match cursor {
+            Some(cursor) => cursor,
+            None => return,
+        };
+        let doc = self.doc_mut();
+        let last = doc.last_line_index();
+        doc.cursor.set_y(cursor.get_y().min(last));
+        doc.cursor.x = cursor.x;
+        // 같은 문서를 보는 다른 창의 커서가 없어진 줄에 남지 않게 한다.
+        let current = self.current;
+        for pane in self.panes.iter_mut().filter(|p| p.document == current) {
+            if pane.cursor.get_y() > last {
+                pane.cursor.set_y(last);
+            }
+        }
+        let doc = self.doc_mut();
+        doc.modified = !doc.history.is_saved();
+        doc.swap_dirty = true;
+        self.update_cursor_x();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 지금 내용을 스왑 파일에 쓴다. 에디터가 비정상 종료되면 다음에 열 때 복구할 수 있다.
+     */
+    fn write_swap(&mut self) {
+        if let Err(err) = self.doc_mut().write_swap() {
+            error!("{}", err);
+            self.notify(err.to_string());
+            self.refresh(RefreshOption::None);
+        }
+    }
+
+    /**
+     * 바뀐 지 SWAP_INTERVAL 이 지난 문서마다 스왑 파일을 쓴다.
+     * 입력이 계속 들어와도 쓰도록 입력을 하나 처리할 때마다 부른다.
+     */
+    fn journal(&mut self) {
+        let mut failed = None;
+        for doc in self
+            .documents
+            .iter_mut()
+            .filter(|doc| doc.swap_dirty && doc.swap_written.elapsed() >= SWAP_INTERVAL)
+        {
+            if let Err(err) = doc.write_swap() {
+                error!("{}", err);
+                failed = Some(err);
+            }
+        }
+        if let Some(err) = failed {
+            self.notify(err.to_string());
+            self.refresh(RefreshOption::None);
+        }
+    }
+
+    /**
+     * SIGHUP(SSH 연결이 끊김)이나 SIGTERM 을 받으면 바뀐 문서마다 스왑 파일을 쓰고 끝낸다.
+     * 스왑 파일은 지우지 않으므로 다음에 열 때 복구할 수 있다.
+     */
+    fn handle_terminate(&mut self, signal: usize) -> ! {
+        info!("Terminated by signal {}", signal);
+        self.flush_swaps();
+        restore_terminal();
+        std::process::exit(128 + signal as i32);
+    }
+
+    /**
+     * SIGHUP, SIGTERM 을 받으면 번호만 적어 두고 입력을 기다리는 사이에 handle_terminate 에서 처리한다.
+     */
+    fn register_signals(&self) {
+        #[cfg(unix)]
+        for signal in [SIGHUP, SIGTERM] {
+            let flag = Arc::clone(&self.terminate_signal);
+            if let Err(e) = signal_hook::flag::register_usize(signal, flag, signal as usize) {
+                error!("Unable to handle signal {}: {}", signal, e);
+            }
+        }
+    }
+
+    /**
+     * 바뀐 문서마다 지금 바로 스왑 파일을 쓴다. 화면에 알릴 수 없으므로 로그에만 남긴다.
+     */
+    fn flush_swaps(&mut self) {
+        for doc in self.documents.iter_mut().filter(|doc| doc.modified) {
+            if let Err(err) = doc.write_swap() {
+                error!("{}", err);
+            }
+        }
+    }
+
+    /**
+     * 저장했거나 정상 종료할 때 스왑 파일을 지운다.
+     */
+    fn remove_swap(&self) {
+        self.doc().remove_swap();
+    }
+
+    /**
+     * 남아 있는 스왑 파일이 있으면 복구할지 묻는다.
+     */
+    fn check_stale_swap(&mut self, path: PathBuf) {
+        if !path.exists() {
+            return;
+        }
+
+        let swap_file = match swap::read(&path) {
+            Ok(swap_file) => swap_file,
+            Err(e) => {
+                self.notify(format!("Ignoring swap file {}: {}", path.display(), e));
+                return;
+            }
+        };
+
+        let owner = if swap::process_running(swap_file.pid) {
+            format!("mdedit (pid {}) may still be editing it.", swap_file.pid)
+        } else {
+            String::from("The editor that wrote it did not exit cleanly.")
+        };
+        let msg = format!(
+            "Found swap file {} with unsaved changes to {}.\n{}",
+            path.display(),
+            self.doc().filename.as_deref().unwrap_or("an untitled file"),
+            owner
+        );
+        let buttons = if self.doc().filename.is_some() {
+            SWAP_BUTTONS
+        } else {
+            UNTITLED_SWAP_BUTTONS
+        };
+
+        self.doc_mut().stale_swap = Some((path, swap_file));
+        self.show_message("swap", Severity::Warning, &msg, Buttons::Custom(buttons));
+    }
+
+    /**
+     * 스왑 파일 대화상자의 답을 처리한다. Esc 로 닫으면 스왑 파일을 건드리지 않도록 읽기 전용으로 연다.
+     */
+    fn close_swap_dialog(&mut self, result: &DialogResult) {
+        // 여러 파일을 열었으면 물어본 순서대로 답한다.
+        if let Some(index) = self
+            .documents
+            .iter()
+            .position(|doc| doc.stale_swap.is_some())
+        {
+            self.switch_document(index);
+        }
+        let (path, swap_file) = match self.doc_mut().stale_swap.take() {
+            Some(stale) => stale,
+            None => return,
+        };
+        // 이름 없는 파일의 스왑 파일은 다른 프로세스 id 로 된 파일이다.
+        let own = self.doc().swap_path.as_ref() == Some(&path);
+
+        match result.button {
+            Some("recover") => {
+                // 복구한 내용은 저장하지 않은 편집이므로 되돌리면 파일 내용으로 돌아간다.
+                let doc = self.doc_mut();
+                let count = doc.contents.len();
+                doc.begin_edit(0, count, false);
+                doc.contents = swap_file
+                    .lines
+                    .iter()
+                    .map(|(line, ending)| LineBuffer::with_ending(line, *ending))
+                    .collect();
+                doc.final_newline = swap_file.final_newline;
+                if doc.contents.is_empty() {
+                    doc.contents
+                        .push(LineBuffer::with_ending("", doc.line_ending));
+                }
+                let count = doc.contents.len();
+                doc.end_edit(count);
+                self.mark_modified();
+                self.write_swap();
+                if !own {
+                    check_result!(swap::remove(&path), "Unable to remove swap file");
+                }
+                self.notify(format!("Recovered unsaved changes from {}", path.display()));
+            }
+            Some("delete") => {
+                check_result!(swap::remove(&path), "Unable to remove swap file");
+            }
+            Some("ignore") => {}
+            _ if own => {
+                self.doc_mut().read_only = true;
+                self.doc_mut().swap_path = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn handle_dialog_event(&mut self, event: DialogEvent) {
+        match event {
+            DialogEvent::None => self.refresh(RefreshOption::None),
+            DialogEvent::Changed(widget) => {
+                let preview = match &self.dialog {
+                    Some(dialog) if dialog.id == display_dialog::ID && widget == "theme" => {
+                        display_dialog::theme_name(&dialog.value(widget))
+                    }
+                    _ => None,
+                };
+                match preview {
+                    Some(name) => self.set_theme(name),
+                    None => self.refresh(RefreshOption::None),
+                }
+            }
+            DialogEvent::Closed(result) => {
+                let id = self.dialog.take().map(|dialog| dialog.id);
+                info!("Dialog {:?} closed: {:?}", id, result.button);
+                match id {
+                    Some(display_dialog::ID) => self.close_display_options(&result),
+                    Some("exit") => self.close_exit_confirm(&result),
+                    Some("swap") => self.close_swap_dialog(&result),
+                    Some("save_as") => self.close_save_as(&result),
+                    Some("replace") => self.close_replace_confirm(&result),
+                    Some("reopen") => self.close_reopen_confirm(&result),
+                    Some("binary") => self.close_binary_confirm(&result),
+                    Some("close") => self.close_close_confirm(&result),
+                    _ => {}
+                }
+                if self.dialog.is_none() {
+                    if let Some(next) = self.dialog_queue.pop_front() {
+                        self.open_dialog(next);
+                    }
+                }
+                self.refresh(RefreshOption::Screen);
+            }
+        }
+    }
+
+    /**
+     * Options > Display
+     */
+    fn handle_display_options(&mut self) {
+        let dialog = display_dialog::new(&DisplayOptions {
+            theme: self.theme.name,
+            color_depth: self.config.color_depth,
+            line_numbers: self.gutter.mode != GutterMode::Off,
+            tab_width: self.config.tab_width,
+        });
+        self.open_dialog(dialog);
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * Options > Display 를 닫으면 고른 설정을 적용한다. 취소했으면 미리 보던 테마를 되돌린다.
+     * (그 실행에서만 적용)
+     */
+    fn close_display_options(&mut self, result: &DialogResult) {
+        match display_dialog::options(result) {
+            Ok(Some(options)) => {
+                info!("Display: {:?}", options);
+                self.config.theme = String::from(options.theme);
+                self.config.color_depth = options.color_depth;
+                self.config.tab_width = options.tab_width;
+                if !options.line_numbers {
+                    self.gutter.mode = GutterMode::Off;
+                } else if self.gutter.mode == GutterMode::Off {
+                    self.gutter.mode = GutterMode::Absolute;
+                }
+            }
+            Ok(None) => {}
+            Err(msg) => self.show_message("display_error", Severity::Error, &msg, Buttons::Ok),
+        }
+
+        let name = self.config.theme.clone();
+        self.set_theme(&name);
+    }
+
+    /**
+     * 설정 파일의 문제를 로그에 남기고 메시지 상자로 알린다. 여러 개면 첫 번째만 보여준다.
+     */
+    fn show_config_problems(&mut self, problems: &[String]) {
+        for problem in problems {
+            error!("config: {}", problem);
+        }
+
+        if let Some(first) = problems.first() {
+            let msg = match problems.len() {
+                1 => first.clone(),
+                n => format!("{} (+{} more, see log)", first, n - 1),
+            };
+            self.show_message("config", Severity::Warning, &msg, Buttons::Ok);
+        }
+    }
+
+    /**
+     * Options > Reload Settings. 설정 파일을 다시 읽어서 재시작 없이 적용한다.
+     */
+    fn reload_settings(&mut self) {
+        let (config, mut problems) = config::load();
+        info!("Reload settings: {:?}", config);
+        problems.extend(self.apply_config(config));
+        self.show_config_problems(&problems);
+        self.refresh(RefreshOption::Screen);
+    }
+
     /**
         에디터의 메인 루프
 
@@ -72,180 +637,1090 @@ impl Editor {
         * main 함수의 리턴값으로 Ok 를 리턴
     */
     pub fn run(&mut self) -> Result<()> {
-        execute!(&self.screen, terminal::EnterAlternateScreen)?;
+        execute!(
+            &self.screen,
+            terminal::EnterAlternateScreen,
+            EnableMouseCapture
+        )?;
         terminal::enable_raw_mode()?;
+        self.update_cursor_shape();
+        self.register_signals();
         self.refresh(RefreshOption::Screen);
 
         loop {
+            let signal = self.terminate_signal.load(Ordering::Relaxed);
+            if signal != 0 {
+                self.handle_terminate(signal);
+            }
+            self.journal();
+
             // command queue 처리. 하나씩
             if self.cmd_queue.size() > 0 {
                 if let Ok(cmd) = self.cmd_queue.peek() {
                     match self.cmd_queue.remove() {
-                        Ok(_) => match cmd {
-                            MenuCmd::Exit => self.goodbye(),
-                            MenuCmd::About => self.handle_help(),
-                            _ => {}
-                        },
+                        Ok(_) => {
+                            if let MenuCmd::Run(id) = cmd {
+                                self.run_command(id);
+                            }
+                        }
                         Err(e) => error!("Failed to remove cmd from queue: {}", e),
                     }
                 }
             }
 
-            let (modifier, code) = match read_char() {
-                Ok((m, c)) => match (m, c) {
-                    // 글로벌 키 처리
-                    (KeyModifiers::CONTROL, KeyCode::Char('q')) => break,
-                    (_, KeyCode::F(12)) => break,
-                    _ => (m, c),
-                },
-                Err(_) => break,
-            };
+            let (modifier, code) = match read_event() {
+                Ok(None) => {
+                    self.tick();
+                    continue;
+                }
+                Ok(Some(Event::Key(KeyEvent {
+                    code: c,
+                    modifiers: m,
+                }))) => {
+                    // 글로벌 키 처리: 메뉴나 팝업이 떠 있어도 동작하는 명령
+                    match self.global_command(KeyBinding::new(m, c)) {
+                        Some(id) => {
+                            self.run_command(id);
+                            continue;
+                        }
+                        None => (m, c),
+                    }
+                }
+                Ok(Some(Event::Mouse(ev))) => {
+                    self.handle_mouse(ev);
+                    continue;
+                }
+                Ok(Some(Event::Resize(width, height))) => {
+                    self.handle_resize(width, height);
+                    continue;
+                }
+                Err(e) => {
+                    // 터미널이 없어졌을 수 있다. (SSH 연결이 끊기면 SIGHUP 보다 먼저 올 수 있다)
+                    let err = EditorError::new(Operation::Input, None, e);
+                    error!("{}", err);
+                    self.flush_swaps();
+                    // 터미널을 되돌린 다음에야 메시지를 볼 수 있다.
+                    restore_terminal();
+                    eprintln!("mdedit: {}", err);
+                    std::process::exit(1);
+                }
+            };
+
+            if self.menu_bar.selected.is_some() {
+                let cmd = self.menu_bar.handle_keyinput(modifier, code);
+                self.handle_menu_cmd(cmd);
+                continue;
+            }
+
+            if let Some(dialog) = &mut self.dialog {
+                let event = dialog.handle_keyinput(modifier, code);
+                self.handle_dialog_event(event);
+                continue;
+            }
+
+            self.handle_keyinput(modifier, code);
+        }
+    }
+
+    /**
+     * 메뉴 막대가 돌려준 명령 처리. 메뉴를 닫거나 다시 그리는 것 외의 명령은 cmd_queue 에 넣는다.
+     */
+    fn handle_menu_cmd(&mut self, cmd: MenuCmd) {
+        match cmd {
+            MenuCmd::None => {}
+            MenuCmd::CloseMenu => {
+                self.menu_bar.selected = None;
+                self.refresh(RefreshOption::Screen);
+            }
+            MenuCmd::Refresh => {
+                self.refresh(RefreshOption::Screen);
+            }
+            _ => {
+                match self.cmd_queue.add(cmd) {
+                    Ok(_) => {}
+                    Err(e) => error!("cmd_queue add error: {}", e),
+                };
+                self.menu_bar.selected = None;
+                self.refresh(RefreshOption::Screen);
+            }
+        }
+    }
+
+    /**
+     * 마우스 입력 처리. 메뉴 > 팝업 > 본문 순서로 이벤트를 넘긴다.
+     */
+    fn handle_mouse(&mut self, ev: MouseEvent) {
+        let clicked = ev.kind == MouseEventKind::Down(MouseButton::Left);
+
+        if self.menu_bar.selected.is_some() || (clicked && ev.row == 0 && self.dialog.is_none()) {
+            let cmd = self.menu_bar.handle_mouse(ev.kind, ev.column, ev.row);
+            self.handle_menu_cmd(cmd);
+            return;
+        }
+
+        if let Some(dialog) = &mut self.dialog {
+            let event = dialog.handle_mouse(ev.kind, ev.column, ev.row);
+            self.handle_dialog_event(event);
+            return;
+        }
+
+        match ev.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.handle_click(ev.column, ev.row),
+            MouseEventKind::ScrollUp => self.handle_wheel(-MOUSE_WHEEL_LINES),
+            MouseEventKind::ScrollDown => self.handle_wheel(MOUSE_WHEEL_LINES),
+            _ => {}
+        }
+    }
+
+    /**
+     * 터미널 크기가 바뀌면 대화상자 위치를 다시 잡고 화면 전체를 다시 그린다.
+     * 커서가 화면 밖으로 나가는 경우는 refresh 에서 스크롤해서 보이게 한다.
+     */
+    fn handle_resize(&mut self, width: u16, height: u16) {
+        info!("resize: width {} height {}", width, height);
+
+        let text_width = self.text_area_width();
+        if self.doc().cursor.x >= text_width {
+            self.doc_mut().cursor.x = text_width.saturating_sub(1);
+            self.update_cursor_x();
+        }
+
+        if let Some(dialog) = &mut self.dialog {
+            dialog.resize(width, height);
+        }
+
+        self.refresh(RefreshOption::Screen);
+    }
+
+    pub fn goodbye(&self) -> ! {
+        for doc in &self.documents {
+            doc.remove_swap();
+        }
+        restore_terminal();
+        std::process::exit(0);
+    }
+
+    /**
+    패닉으로 멈췄을 때 저장하지 않은 문서마다 내용을 복구 파일에 쓴다.
+    파일 옆에 `<파일 이름>.recovered` 로 쓰고, 실패하면 임시 디렉터리에 쓴다.
+
+    # Return
+    * 바뀐 문서마다 저장한 복구 파일 경로 또는 에러. 바뀐 문서가 없으면 비어 있다.
+    */
+    pub fn emergency_save(&self) -> Vec<std::result::Result<PathBuf, EditorError>> {
+        self.documents
+            .iter()
+            .enumerate()
+            .filter(|(_, doc)| doc.modified)
+            .map(|(i, doc)| {
+                let name = match i {
+                    0 => format!("mdedit-{}.recovered", std::process::id()),
+                    i => format!("mdedit-{}-{}.recovered", std::process::id(), i),
+                };
+                let fallback = env::temp_dir().join(name);
+                let path = match &doc.filename {
+                    Some(name) => PathBuf::from(format!("{}.recovered", name)),
+                    None => fallback.clone(),
+                };
+
+                match write_file(doc, &path) {
+                    Ok(_) => Ok(path),
+                    Err(err) if path != fallback => {
+                        error!("{}", err);
+                        write_file(doc, &fallback).map(|_| fallback)
+                    }
+                    Err(err) => Err(err),
+                }
+            })
+            .collect()
+    }
+
+    /**
+     * 한 키로 된 글로벌 명령. 여러 키 조합을 입력하는 중에는 찾지 않는다.
+     */
+    fn global_command(&self, key: KeyBinding) -> Option<CommandId> {
+        if !self.pending_keys.is_empty() {
+            return None;
+        }
+
+        match self.keymap.lookup(&[key]) {
+            KeyMatch::Command(id) => match self.commands.get(id) {
+                Some(command) if command.global => Some(command.id),
+                _ => None,
+            },
+            _ => None,
+        }
+    }
+
+    /**
+     * 본문 편집 중의 키 입력 처리. 키 바인딩에 연결된 명령이 있으면 실행하고, 없으면 글자를 입력한다.
+     */
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) {
+        self.pending_keys.push(KeyBinding::new(modifier, code));
+        match self.keymap.lookup(&self.pending_keys) {
+            KeyMatch::Command(id) => {
+                let sequence = std::mem::take(&mut self.pending_keys);
+                self.run_command(id);
+                if sequence.len() > 1 {
+                    // 상태 표시줄의 입력 중인 키 표시를 지운다.
+                    self.refresh(RefreshOption::None);
+                }
+                return;
+            }
+            KeyMatch::Prefix => {
+                // 나머지 키를 기다린다. 상태 표시줄에 지금까지 누른 키를 보여준다.
+                self.refresh(RefreshOption::None);
+                return;
+            }
+            KeyMatch::None => {
+                let sequence = std::mem::take(&mut self.pending_keys);
+                if sequence.len() > 1 {
+                    info!("Unbound key sequence: {}", chord_label(&sequence));
+                    self.refresh(RefreshOption::None);
+                    return;
+                }
+            }
+        }
+
+        match (modifier, code) {
+            (KeyModifiers::ALT, KeyCode::Char(c)) => self.handle_menu_mnemonic(c),
+            (_, KeyCode::Char(c)) if !self.doc().read_only => match &mut self.doc_mut().hex {
+                Some(hex) => {
+                    if hex.input_char(c) {
+                        self.mark_modified();
+                        self.refresh(RefreshOption::Screen);
+                    }
+                }
+                None => self.handle_input_char(c),
+            },
+            _ => {} // do nothing
+        }
+    }
+
+    /**
+    명령 목록에 등록된 명령을 실행한다. 비활성 상태인 명령은 실행하지 않는다.
+
+    # Arguments
+    * `id` - 명령 id
+    */
+    fn run_command(&mut self, id: &str) {
+        let (enabled, handler) = match self.commands.get(id) {
+            Some(command) => (command.enabled, command.handler),
+            None => {
+                error!("Unknown command: {}", id);
+                return;
+            }
+        };
+
+        if enabled(self) {
+            handler(self);
+        } else {
+            info!("Command is disabled: {}", id);
+        }
+    }
+
+    /**
+        파일을 열고 내용을 읽어들인다.
+
+        # Arguments
+        * `filename` - 파일 이름
+    */
+    fn open_file(&mut self, filename: &String) {
+        let encoding = self.config.encoding;
+        let doc = self.doc_mut();
+        doc.contents.clear();
+        doc.hex = None;
+        doc.filename = Some(filename.clone());
+        doc.modified = false;
+        doc.line_ending = LineEnding::native();
+        doc.final_newline = true;
+        doc.encoding = encoding;
+        doc.swap_path = Some(swap::swap_path(Some(filename)));
+
+        let path = Path::new(filename);
+        match fs::File::open(path) {
+            Ok(mut file) => match read_bytes(&mut file) {
+                Ok(bytes) => {
+                    let decoded = encoding::decode_auto(&bytes, self.config.encoding);
+                    if decoded.binary {
+                        // 답을 받을 때까지 빈 버퍼로 둔다.
+                        let msg = format!(
+                            "{} does not look like a text file. Open it anyway?\n\
+                             Bytes that are not text are shown as \\xNN and saved unchanged.",
+                            filename
+                        );
+                        self.doc_mut().pending_binary = Some(decoded);
+                        self.show_message(
+                            "binary",
+                            Severity::Warning,
+                            &msg,
+                            Buttons::Custom(BINARY_BUTTONS),
+                        );
+                        let doc = self.doc_mut();
+                        doc.contents
+                            .push(LineBuffer::with_ending("", doc.line_ending));
+                        return;
+                    }
+                    self.load_decoded(decoded);
+                }
+                Err(e) => {
+                    // 읽다 만 내용으로 덮어쓰지 않도록 새 파일로 취급한다.
+                    self.doc_mut().filename = None;
+                    self.report_error(EditorError::new(Operation::Read, Some(path), e));
+                }
+            },
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                self.notify(format!("New file: {}", filename));
+            }
+            Err(e) => {
+                self.doc_mut().filename = None;
+                self.report_error(EditorError::new(Operation::Open, Some(path), e));
+            }
+        }
+
+        self.finish_open();
+    }
+
+    /**
+     * 파일을 열고 난 뒤 (또는 열지 않기로 한 뒤) 빈 버퍼를 채우고 남아 있는 스왑 파일을 확인한다.
+     */
+    fn finish_open(&mut self) {
+        let doc = self.doc_mut();
+        if doc.contents.is_empty() {
+            doc.contents
+                .push(LineBuffer::with_ending("", doc.line_ending));
+        }
+        doc.reset_history();
+
+        match &self.doc().swap_path {
+            Some(path) if self.doc().filename.is_some() => self.check_stale_swap(path.clone()),
+            _ => self.doc_mut().swap_path = Some(self.untitled_swap_path()),
+        }
+    }
+
+    /**
+     * 이미 열려 있는 파일이면 그 문서의 인덱스
+     */
+    fn find_document(&self, filename: &str) -> Option<usize> {
+        self.documents
+            .iter()
+            .position(|doc| doc.filename.as_deref() == Some(filename))
+    }
+
+    /**
+     * 이름 없는 문서의 스왑 파일 위치. 다른 이름 없는 문서와 겹치지 않게 번호를 붙인다.
+     */
+    fn untitled_swap_path(&self) -> PathBuf {
+        let in_use = |path: &PathBuf| {
+            self.documents
+                .iter()
+                .any(|doc| doc.swap_path.as_ref() == Some(path))
+        };
+        (0..)
+            .map(swap::untitled_swap_path)
+            .find(|path| !in_use(path))
+            .unwrap_or_else(|| swap::swap_path(None))
+    }
+
+    /**
+     * File > New. 이름 없는 빈 문서를 만들어서 편집한다.
+     */
+    fn new_document(&mut self) {
+        let swap_path = self.untitled_swap_path();
+        self.documents
+            .push(Document::new(self.config.encoding, Some(swap_path)));
+        self.switch_document(self.documents.len() - 1);
+    }
+
+    /**
+     * 편집할 문서를 바꾼다. (Window 메뉴) 문서마다 커서와 스크롤 위치가 따로 있다.
+     */
+    fn switch_document(&mut self, index: usize) {
+        if index >= self.documents.len() {
+            return;
+        }
+        self.leave_document();
+
+        self.current = index;
+        self.panes[self.focus].document = index;
+        self.update_cursor_x();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 스왑 파일은 편집 중인 문서만 쓰므로 다른 문서로 옮기기 전에 써 둔다.
+     */
+    fn leave_document(&mut self) {
+        if self.doc().swap_dirty {
+            self.write_swap();
+        }
+    }
+
+    fn next_document(&mut self) {
+        self.switch_document((self.current + 1) % self.documents.len());
+    }
+
+    fn previous_document(&mut self) {
+        let len = self.documents.len();
+        self.switch_document((self.current + len - 1) % len);
+    }
+
+    /**
+     * Window > Split. 커서가 있는 창을 나누고 새 창(아래 또는 오른쪽)에도 같은 문서를 보여준다.
+     */
+    fn split_pane(&mut self, direction: SplitDirection) {
+        if !self.pane_areas()[self.focus].can_split(direction) {
+            self.notify("Not enough room to split the window".to_string());
+            return;
+        }
+
+        let new_pane = self.panes.len();
+        self.panes.push(Pane {
+            document: self.current,
+            cursor: self.doc().cursor,
+            hex_top: self.doc().hex.as_ref().map_or(0, |hex| hex.top()),
+        });
+        self.layout.split(self.focus, direction, new_pane);
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 커서를 다른 창으로 옮긴다. 떠나는 창의 커서는 창에 두고 들어가는 창의 커서를 문서에 넣는다.
+     */
+    fn focus_pane(&mut self, index: usize) {
+        if index == self.focus || index >= self.panes.len() {
+            return;
+        }
+        self.leave_document();
+
+        self.panes[self.focus].cursor = self.doc().cursor;
+        if let Some(hex) = &self.doc().hex {
+            self.panes[self.focus].hex_top = hex.top();
+        }
+        self.focus = index;
+        self.current = self.panes[index].document;
+        self.doc_mut().cursor = self.panes[index].cursor;
+        let hex_top = self.panes[index].hex_top;
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.set_top(hex_top);
+        }
+        self.update_cursor_x();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn next_pane(&mut self) {
+        self.focus_pane((self.focus + 1) % self.panes.len());
+    }
+
+    /**
+     * 커서가 있는 창을 키우거나 줄인다. (%)
+     */
+    fn resize_pane(&mut self, delta: i16) {
+        let area = self.edit_area();
+        if self.layout.resize(area, self.focus, delta) {
+            self.refresh(RefreshOption::Screen);
+        }
+    }
+
+    /**
+     * 커서가 있는 창을 닫는다. 문서는 닫지 않는다.
+     */
+    fn close_pane(&mut self) {
+        if self.panes.len() < 2 {
+            return;
+        }
+        self.leave_document();
+
+        let closed = self.focus;
+        self.layout.remove(closed);
+        self.panes.remove(closed);
+        self.focus = closed.min(self.panes.len() - 1);
+        self.current = self.panes[self.focus].document;
+        self.doc_mut().cursor = self.panes[self.focus].cursor;
+        self.update_cursor_x();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * File > Close. 바뀐 내용이 있으면 저장할지 먼저 묻는다.
+     */
+    fn handle_close(&mut self) {
+        if !self.doc().modified {
+            self.close_document();
+            return;
+        }
+
+        let msg = format!("Save changes to {}?", self.doc().title());
+        self.show_message("close", Severity::Warning, &msg, Buttons::YesNoCancel);
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn close_close_confirm(&mut self, result: &DialogResult) {
+        match result.button {
+            Some("yes") => {
+                // 이름이 없으면 Save As 로 이름을 받은 다음 닫는다.
+                self.close_after_save = self.doc().filename.is_none();
+                self.handle_save();
+                if !self.doc().modified {
+                    self.close_document();
+                }
+            }
+            Some("no") => self.close_document(),
+            _ => {}
+        }
+    }
+
+    /**
+     * 편집 중인 문서를 저장하지 않고 닫는다. 마지막 문서를 닫으면 이름 없는 빈 문서를 만든다.
+     */
+    fn close_document(&mut self) {
+        self.remove_swap();
+        let closed = self.current;
+        self.documents.remove(closed);
+        if self.documents.is_empty() {
+            let swap_path = self.untitled_swap_path();
+            self.documents
+                .push(Document::new(self.config.encoding, Some(swap_path)));
+        }
+        self.current = self.current.min(self.documents.len() - 1);
+
+        // 닫은 문서를 보여주던 창은 모두 다음 문서를 보여준다.
+        for pane in &mut self.panes {
+            if pane.document == closed {
+                pane.document = self.current;
+                pane.cursor = self.documents[self.current].cursor;
+            } else if pane.document > closed {
+                pane.document -= 1;
+            }
+        }
+        self.update_cursor_x();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn close_binary_confirm(&mut self, result: &DialogResult) {
+        if let Some(index) = self
+            .documents
+            .iter()
+            .position(|doc| doc.pending_binary.is_some())
+        {
+            self.switch_document(index);
+        }
+        let decoded = self.doc_mut().pending_binary.take();
+        match (result.button, decoded) {
+            (Some("open"), Some(decoded)) => self.load_decoded(decoded),
+            _ => {
+                self.doc_mut().filename = None;
+                self.doc_mut().swap_path = None;
+            }
+        }
+        self.finish_open();
+    }
+
+    /**
+     * 디코딩한 파일 내용을 버퍼에 넣는다. 보존한 바이트가 있으면 알린다.
+     */
+    fn load_decoded(&mut self, decoded: Decoded) {
+        self.doc_mut().encoding = decoded.encoding;
+        self.doc_mut().load_text(&decoded.text);
+        if decoded.escaped > 0 {
+            self.notify(format!(
+                "{} bytes that are not valid text are shown as \\xNN and saved unchanged",
+                decoded.escaped
+            ));
+        }
+    }
+
+    fn text(&self) -> String {
+        self.doc().text()
+    }
+
+    fn uniform_line_ending(&self) -> Option<LineEnding> {
+        self.doc().uniform_line_ending()
+    }
+
+    /**
+     * 모든 줄의 줄 끝 문자를 바꾼다. (File > Line Endings)
+     */
+    fn set_line_ending(&mut self, ending: LineEnding) {
+        if self.uniform_line_ending() != Some(ending) {
+            let doc = self.doc_mut();
+            let count = doc.contents.len();
+            doc.begin_edit(0, count, false);
+            for line in doc.contents.iter_mut() {
+                line.set_ending(ending);
+            }
+            doc.end_edit(count);
+            self.mark_modified();
+        }
+        self.doc_mut().line_ending = ending;
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn toggle_final_newline(&mut self) {
+        let doc = self.doc_mut();
+        doc.begin_edit(0, 0, false);
+        doc.final_newline = !doc.final_newline;
+        doc.end_edit(0);
+        self.mark_modified();
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 저장할 때의 인코딩을 바꾼다. (File > Save with Encoding)
+     * 지금 내용에 그 인코딩으로 나타낼 수 없는 글자가 있으면 바꾸지 않고 알린다.
+     */
+    fn set_encoding(&mut self, encoding: Encoding) {
+        if let Err(chars) = encoding::encode(&self.text(), encoding) {
+            let msg = format!(
+                "Cannot convert to {}: {} cannot be represented.",
+                encoding.label(),
+                char_list(&chars)
+            );
+            self.show_message("error", Severity::Warning, &msg, Buttons::Ok);
+        } else if encoding != self.doc().encoding {
+            // 인코딩은 되돌리기 기록에 없으므로 내용을 되돌려도 저장한 파일과 다르다.
+            let doc = self.doc_mut();
+            doc.encoding = encoding;
+            doc.history.forget_saved();
+            self.mark_modified();
+        }
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 파일을 다른 인코딩으로 다시 읽는다. (File > Reopen with Encoding)
+     * 바뀐 내용이 있으면 버릴지 먼저 묻는다.
+     */
+    fn reopen_with_encoding(&mut self, charset: Charset) {
+        let filename = match &self.doc().filename {
+            Some(filename) => filename.clone(),
+            None => return,
+        };
+        if self.doc().modified {
+            let msg = format!(
+                "Discard changes to {} and reopen it as {}?",
+                filename,
+                Encoding::new(charset, false).label()
+            );
+            self.pending_reopen = Some(charset);
+            self.show_message("reopen", Severity::Warning, &msg, Buttons::YesNo);
+            self.refresh(RefreshOption::Screen);
+        } else {
+            self.reopen(&filename, charset);
+        }
+    }
+
+    fn close_reopen_confirm(&mut self, result: &DialogResult) {
+        if let (Some("yes"), Some(charset), Some(filename)) = (
+            result.button,
+            self.pending_reopen.take(),
+            self.doc().filename.clone(),
+        ) {
+            self.reopen(&filename, charset);
+        }
+    }
+
+    /**
+     * UTF-16 으로 읽을 수 없는 바이트가 있으면 U+FFFD 로 바꿔서 보여주고,
+     * 저장해서 원래 파일을 망가뜨리지 않도록 읽기 전용으로 연다.
+     */
+    fn reopen(&mut self, filename: &str, charset: Charset) {
+        let path = Path::new(filename);
+        let bytes = match fs::File::open(path) {
+            Ok(mut file) => match read_bytes(&mut file) {
+                Ok(bytes) => bytes,
+                Err(e) => {
+                    return self.report_error(EditorError::new(Operation::Read, Some(path), e))
+                }
+            },
+            Err(e) => return self.report_error(EditorError::new(Operation::Open, Some(path), e)),
+        };
+
+        let encoding = Encoding::with_bom_of(charset, &bytes);
+        let (decoded, had_errors) = encoding::decode(&bytes, encoding);
+        let escaped = decoded.escaped;
+        self.load_decoded(decoded);
+        let doc = self.doc_mut();
+        doc.cursor = Cursor::new();
+        doc.modified = false;
+        doc.swap_dirty = false;
+        self.remove_swap();
+
+        if had_errors {
+            self.doc_mut().read_only = true;
+            self.doc_mut().swap_path = None;
+            let msg = format!(
+                "{} is not valid {} text. Invalid bytes are shown as U+FFFD \
+                 and the file is opened read-only.",
+                filename,
+                encoding.label()
+            );
+            self.show_message("error", Severity::Warning, &msg, Buttons::Ok);
+        } else if escaped == 0 {
+            self.notify(format!("Reopened {} as {}", filename, encoding.label()));
+        }
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * 현재 커서가 있는 한 줄 갱신
+     */
+    fn refresh(&mut self, opt: RefreshOption) {
+        let areas = self.pane_areas();
+        let area = areas[self.focus];
+
+        // View > Hex 는 바이트 하나를 고쳐도 글자 영역까지 바뀌므로 항상 전부 그린다.
+        // 같은 문서를 다른 창에서도 보고 있으면 고친 줄이 그 창에도 보이도록 전부 그린다.
+        let shared = self
+            .panes
+            .iter()
+            .enumerate()
+            .any(|(i, pane)| i != self.focus && pane.document == self.current);
+        let opt = match opt {
+            _ if self.doc().hex.is_some() => RefreshOption::Screen,
+            RefreshOption::Line if shared => RefreshOption::Screen,
+            opt => opt,
+        };
+
+        let gutter_width = self.gutter.width(self.doc().contents.len());
+        let doc = self.doc_mut();
+        doc.cursor.set_origin(area.x, area.y);
+        if let Some(hex) = &mut doc.hex {
+            hex.layout(area.w as usize, area.h as usize);
+        }
+
+        // 줄 수의 자릿수가 바뀌면 줄 번호 영역 너비도 바뀌므로 전체를 다시 그린다.
+        let opt = if gutter_width != doc.cursor.get_x_offset() {
+            doc.cursor.set_x_offset(gutter_width);
+            RefreshOption::Screen
+        } else {
+            opt
+        };
 
-            if self.menu_bar.selected.is_some() {
-                let cmd = self.menu_bar.handle_keyinput(modifier, code);
+        // 커서가 화면 밖으로 나가면 스크롤 후 전체를 다시 그린다.
+        let opt = if doc.cursor.scroll_into_view(area.h) {
+            RefreshOption::Screen
+        } else {
+            opt
+        };
 
-                match cmd {
-                    MenuCmd::CloseMenu => {
-                        self.menu_bar.selected = None;
-                        self.refresh(RefreshOption::Screen);
-                    }
-                    MenuCmd::Refresh => {
-                        self.refresh(RefreshOption::Screen);
-                    }
-                    _ => {
-                        match self.cmd_queue.add(cmd) {
-                            Ok(_) => {}
-                            Err(e) => error!("cmd_queue add error: {}", e),
-                        };
-                        self.menu_bar.selected = None;
-                        self.refresh(RefreshOption::Screen);
+        match opt {
+            RefreshOption::Line => {
+                let doc = self.doc();
+                let y = doc.cursor.get_y() as usize;
+                if let Some(line) = doc.contents.get(y) {
+                    let x = area.x + gutter_width;
+                    match queue!(
+                        &self.screen,
+                        crossterm::cursor::MoveTo(x, doc.cursor.screen_y())
+                    ) {
+                        Ok(_) => {
+                            let text_width =
+                                fill_width(&area).saturating_sub(gutter_width as usize);
+                            line.draw(text_width, self.line_colors(doc, line))
+                        }
+                        Err(e) => error!("Failed to move cursor: {}", e),
                     }
                 }
-
-                continue;
             }
+            RefreshOption::Screen => {
+                // 지운 화면이 본문 배경색으로 채워지도록 색을 먼저 정한다.
+                set_color(self.theme.text.fg, self.theme.text.bg);
+                if let Err(e) = queue!(&self.screen, Clear(ClearType::All)) {
+                    error!("Failed to clear screen: {}", e);
+                }
 
-            match &self.popup {
-                None => self.handle_keyinput(modifier, code),
-                Some(p) => {
-                    if p.handle_keyinput(modifier, code) {
-                        self.popup = None;
-                        self.refresh(RefreshOption::Screen)
-                    }
+                for (index, area) in areas.iter().enumerate() {
+                    self.draw_pane(index, area);
                 }
+                set_color(self.theme.text.fg, self.theme.text.bg);
+                self.layout.draw_separators(&self.screen, self.edit_area());
+
+                self.draw_menu_bar();
             }
+            _ => {}
         }
 
-        self.goodbye();
-        Ok(())
-    }
+        self.draw_gutter(self.focus, &area);
+        self.draw_status_bar();
+        if self.debug {
+            self.print_dbgmsg();
+        }
 
-    pub fn goodbye(&self) {
-        execute!(&self.screen, terminal::LeaveAlternateScreen).unwrap();
-        check_result!(terminal::disable_raw_mode(), "Unable to disable raw mode");
-        std::process::exit(0);
-    }
+        // 대화상자는 위젯 값이 바뀔 때마다 다시 그리고, 커서도 포커스를 가진 위젯에 둔다.
+        let cursor = match &self.doc().hex {
+            // 창이 좁아서 잘린 곳에 있으면 창의 오른쪽 끝에 둔다.
+            Some(hex) => {
+                let (x, y) = hex.cursor_position(area.x, area.y);
+                (x.min(area.x + area.w.saturating_sub(1)), y)
+            }
+            None => (self.doc().cursor.screen_x(), self.doc().cursor.screen_y()),
+        };
+        let (x, y) = match &self.dialog {
+            Some(dialog) => {
+                dialog.draw(&self.theme);
+                dialog.cursor_position().unwrap_or(cursor)
+            }
+            None => cursor,
+        };
+        check_result!(
+            queue!(&self.screen, crossterm::cursor::MoveTo(x, y)),
+            "Failed to move cursor"
+        );
 
-    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) {
-        match (modifier, code) {
-            (KeyModifiers::CONTROL, KeyCode::Char('s')) => self.handle_save(),
-            (KeyModifiers::NONE, KeyCode::F(1)) => self.handle_help(),
-            (KeyModifiers::NONE, KeyCode::F(10)) => self.handle_menu(),
-            (_, KeyCode::Backspace) => self.handle_backspace(),
-            (_, KeyCode::Char(c)) => self.handle_input_char(c),
-            (KeyModifiers::NONE, KeyCode::Enter) => self.handle_enterkey(),
-            (KeyModifiers::NONE, KeyCode::Left) => self.handle_leftkey(),
-            (KeyModifiers::NONE, KeyCode::Right) => self.handle_rightkey(),
-            (KeyModifiers::NONE, KeyCode::Up) => self.handle_upkey(),
-            (KeyModifiers::NONE, KeyCode::Down) => self.handle_downkey(),
-            _ => {} // do nothing
+        if let Err(e) = Write::flush(&mut self.screen) {
+            // 화면이 돌아오면 다음 refresh 때 보인다.
+            let err = EditorError::new(Operation::Render, None, e);
+            error!("{}", err);
+            self.notify(err.to_string());
         }
     }
 
     /**
-        파일을 열고 내용을 읽어들인다.
+     * 창 하나의 본문과 줄 번호를 그린다. 커서가 없는 창은 그 창에 저장한 스크롤 위치로 그린다.
+     */
+    fn draw_pane(&self, index: usize, area: &Area) {
+        let doc = &self.documents[self.panes[index].document];
+        if let Some(hex) = &doc.hex {
+            let top = if index == self.focus {
+                hex.top()
+            } else {
+                self.panes[index].hex_top
+            };
+            hex.draw(&self.screen, area, top, fill_width(area), &self.theme);
+            return;
+        }
 
-        # Arguments
-        * `filename` - 파일 이름
+        let gutter_width = self.gutter.width(doc.contents.len());
+        let text_width = fill_width(area).saturating_sub(gutter_width as usize);
+        let top = self.pane_cursor(index).get_top() as usize;
+        for (line_count, line) in doc
+            .contents
+            .iter()
+            .skip(top)
+            .take(area.h as usize)
+            .enumerate()
+        {
+            info!(
+                "화면에 그리기: pane {} y {} line {:?}",
+                index, line_count, line
+            );
+            if let Err(e) = queue!(
+                &self.screen,
+                crossterm::cursor::MoveTo(area.x + gutter_width, area.y + line_count as u16)
+            ) {
+                error!("Failed to move cursor: {}", e);
+                return;
+            }
+            line.draw(text_width, self.line_colors(doc, line));
+        }
+
+        self.draw_gutter(index, area);
+    }
+
+    /**
+    View > Hex. 지금 내용을 파일의 인코딩으로 바꾼 바이트를 보여주고, 다시 고르면 텍스트로 돌아간다.
+    바이트를 고쳤으면 같은 인코딩으로 다시 읽어서 내용에 반영한다.
     */
-    fn open_file(&mut self, filename: &String) {
-        self.contents.clear();
-
-        match File::open(filename) {
-            Ok(file) => {
-                for line in io::BufReader::new(file).lines() {
-                    info!("line = {:?}", line);
-                    match line {
-                        Ok(l) => self.contents.push(LineBuffer::from(&l)),
-                        Err(e) => error!("Failed to read line: {}", e),
+    fn toggle_hex_view(&mut self) {
+        match self.doc_mut().hex.take() {
+            None => match encoding::encode(&self.text(), self.doc().encoding) {
+                Ok(bytes) => {
+                    self.doc_mut().hex = Some(HexView::new(bytes));
+                    let current = self.current;
+                    for pane in self.panes.iter_mut().filter(|p| p.document == current) {
+                        pane.hex_top = 0;
                     }
                 }
+                Err(chars) => {
+                    let msg = format!(
+                        "Cannot show the bytes as {}: {} cannot be represented.",
+                        self.doc().encoding.label(),
+                        char_list(&chars)
+                    );
+                    self.show_message("error", Severity::Error, &msg, Buttons::Ok);
+                }
+            },
+            Some(hex) if hex.modified() => {
+                let bytes = hex.into_bytes();
+                let encoding = Encoding::with_bom_of(self.doc().encoding.charset, &bytes);
+                let (decoded, had_errors) = encoding::decode(&bytes, encoding);
+                let doc = self.doc_mut();
+                doc.encoding = decoded.encoding;
+                // 바이트를 고친 것을 편집 한 번으로 기록해서 그 전의 텍스트 편집도 되돌릴 수 있게 한다.
+                doc.replace_text(&decoded.text);
+                doc.cursor = Cursor::new();
+                // Hex 에서 저장했으면 바뀐 것이 없다. 스왑 파일은 Hex 에서 쓰지 않았으므로 지금 쓴다.
+                if !doc.modified {
+                    doc.history.mark_saved();
+                }
+                doc.swap_dirty = doc.modified;
+                if had_errors {
+                    let msg = format!(
+                        "Some bytes are not valid {} and were replaced with U+FFFD.",
+                        encoding.label()
+                    );
+                    self.show_message("error", Severity::Warning, &msg, Buttons::Ok);
+                }
             }
-            Err(e) => error!("Failed to open file: {}", e),
+            Some(_) => {}
         }
+        self.refresh(RefreshOption::Screen);
     }
 
     /**
-     * 현재 커서가 있는 한 줄 갱신
+     * 마크다운 파일(이름이 없는 새 파일 포함)이면 줄 종류에 따라 색을 다르게 한다.
      */
-    fn refresh(&mut self, opt: RefreshOption) {
-        queue!(
-            &self.screen,
-            crossterm::cursor::MoveTo(0, self.cursor.screen_y())
-        )
-        .expect("Failed to move cursor");
+    fn line_colors(&self, doc: &Document, line: &LineBuffer) -> ColorPair {
+        let markdown = match &doc.filename {
+            None => true,
+            Some(name) => {
+                let name = name.to_ascii_lowercase();
+                name.ends_with(".md") || name.ends_with(".markdown")
+            }
+        };
 
-        let mut line_count = 0;
-        let screen_width = screen_width();
+        let kind = if markdown {
+            markdown_line_kind(line.get_buffer())
+        } else {
+            TokenKind::Text
+        };
+        self.theme.token(kind)
+    }
 
-        match opt {
-            RefreshOption::Line => {
-                if let Some(line) = self.current_line() {
-                    line.draw(screen_width)
-                }
-            }
-            RefreshOption::Screen => {
-                queue!(&self.screen, Clear(ClearType::All)).unwrap();
-
-                for line in &self.contents {
-                    info!(
-                        "화면에 그리기: x {} y {} line {:?}",
-                        line_count,
-                        self.cursor.screen_y(),
-                        line
-                    );
-                    queue!(&self.screen, crossterm::cursor::MoveTo(0, line_count))
-                        .expect("Failed to move cursor");
-                    line.draw(screen_width);
-                    line_count += 1;
-                }
+    /**
+     * 창 하나의 줄 번호 영역을 그린다.
+     * 상대 번호일 때는 커서가 움직일 때마다 모든 번호가 바뀌므로 매번 전부 그린다.
+     */
+    fn draw_gutter(&self, index: usize, area: &Area) {
+        let doc = &self.documents[self.panes[index].document];
+        if doc.hex.is_some() {
+            return;
+        }
+        let cursor = self.pane_cursor(index);
+        let total_lines = doc.contents.len();
+        let current = cursor.get_y() as usize;
+        let top = cursor.get_top() as usize;
+        let max_lines = area.h as usize;
 
-                self.menu_bar.draw(&self.screen, screen_width);
-            }
-            _ => {}
+        for line in top..std::cmp::min(total_lines, top + max_lines) {
+            self.gutter.draw(
+                &self.screen,
+                (area.x, area.y + (line - top) as u16),
+                line,
+                current,
+                total_lines,
+                &self.theme,
+            );
         }
+    }
 
-        // 디버그 정보 출력
-        self.print_dbgmsg();
+    fn draw_menu_bar(&mut self) {
+        let titles: Vec<&str> = self.documents.iter().map(Document::title).collect();
+        self.menu_bar.set_window_list(&titles);
 
-        queue!(
+        let state = self.menu_item_state();
+        self.menu_bar.draw(
             &self.screen,
-            crossterm::cursor::MoveTo(self.cursor.x, self.cursor.screen_y())
-        )
-        .expect("Failed to move cursor");
+            screen_width(),
+            screen_height(),
+            &state,
+            &self.theme,
+        );
+    }
+
+    /**
+     * 메뉴 항목의 활성/체크 상태를 명령 목록의 조건으로 계산하는 함수를 만든다.
+     */
+    fn menu_item_state(&self) -> impl Fn(MenuCmd) -> ItemState {
+        let states: HashMap<&str, ItemState> = self
+            .commands
+            .iter()
+            .map(|command| {
+                let state = ItemState {
+                    enabled: (command.enabled)(self),
+                    checked: command.checked.map(|checked| checked(self)),
+                };
+                (command.id, state)
+            })
+            .collect();
+
+        move |cmd| match cmd {
+            MenuCmd::Run(id) => states.get(id).copied().unwrap_or(ItemState {
+                enabled: false,
+                checked: None,
+            }),
+            _ => ItemState::enabled(),
+        }
+    }
+
+    fn toggle_line_numbers(&mut self) {
+        self.gutter.mode = match self.gutter.mode {
+            GutterMode::Off => GutterMode::Absolute,
+            _ => GutterMode::Off,
+        };
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn set_gutter_mode(&mut self, mode: GutterMode) {
+        self.gutter.mode = mode;
+        self.refresh(RefreshOption::Screen);
+    }
 
-        check_result!(Write::flush(&mut self.screen), "Failed to put char");
+    fn draw_status_bar(&mut self) {
+        let char_index = match self.current_line() {
+            Some(line) => line.char_index(),
+            None => 0,
+        };
+
+        let pending_keys = chord_label(&self.pending_keys);
+        let message = self
+            .status_message
+            .as_ref()
+            .map_or("", |(msg, _)| msg.as_str());
+        let info = StatusInfo {
+            filename: self.doc().filename.as_deref(),
+            modified: self.doc().modified,
+            read_only: self.doc().read_only,
+            line: self.doc().cursor.get_y() as usize + 1,
+            total_lines: self.doc().contents.len(),
+            column: self.doc().cursor.x as usize + 1,
+            char_index: char_index + 1,
+            encoding: self.doc().encoding.label(),
+            line_ending: self
+                .uniform_line_ending()
+                .map_or("Mixed", LineEnding::label),
+            overwrite: self.overwrite,
+            hex_offset: self
+                .doc()
+                .hex
+                .as_ref()
+                .map(|hex| (hex.offset(), hex.bytes().len())),
+            pending_keys: &pending_keys,
+            message,
+        };
+        StatusBar::draw(
+            &self.screen,
+            screen_height() - 1,
+            screen_width(),
+            &info,
+            &self.theme,
+        );
     }
 
+    /**
+     * --debug 옵션을 줬을 때만 상태 표시줄 바로 위에 현재 줄 내용과 커서 위치를 출력한다.
+     */
     fn print_dbgmsg(&mut self) {
-        queue!(
+        if let Err(e) = queue!(
             &self.screen,
-            crossterm::cursor::MoveTo(0, screen_height() - 1)
-        )
-        .expect("Failed to move cursor");
+            crossterm::cursor::MoveTo(0, screen_height() - 2)
+        ) {
+            error!("Failed to move cursor: {}", e);
+        }
 
-        let x = self.cursor.x;
-        let y = self.cursor.screen_y();
+        let x = self.doc().cursor.x;
+        let y = self.doc().cursor.screen_y();
         print!(
             "current_line: {:?} cx {:?} cy {:?}",
             self.current_line(),
@@ -254,34 +1729,44 @@ impl Editor {
         );
     }
 
+    fn doc(&self) -> &Document {
+        &self.documents[self.current]
+    }
+
+    fn doc_mut(&mut self) -> &mut Document {
+        &mut self.documents[self.current]
+    }
+
     fn current_line(&mut self) -> Option<&mut LineBuffer> {
-        if self.cursor.get_y() < self.contents.len() as u16 {
-            Some(&mut self.contents[self.cursor.get_y() as usize])
-        } else {
-            None
-        }
+        self.doc_mut().current_line()
     }
 
     fn add_new_line(&mut self) {
-        if self.cursor.get_y() < self.edit_area_height() {
-            self.contents.push(LineBuffer::new());
-            self.cursor.x = 0;
-            self.cursor.move_down(self.edit_area_height());
-        }
+        let doc = self.doc_mut();
+        let end = doc.contents.len();
+        doc.begin_edit(end, 0, false);
+        doc.contents
+            .push(LineBuffer::with_ending("", doc.line_ending));
+        doc.end_edit(1);
+        doc.cursor.x = 0;
+        let last = doc.last_line_index();
+        doc.cursor.move_down(last);
+        self.mark_modified();
     }
 
     fn move_up(&mut self) {
-        self.cursor.move_up();
+        self.doc_mut().cursor.move_up();
         self.update_cursor_x();
     }
 
     fn move_down(&mut self) {
-        self.cursor.move_down(self.edit_area_height());
+        let last = self.last_line_index();
+        self.doc_mut().cursor.move_down(last);
         self.update_cursor_x();
     }
 
     fn update_cursor_x(&mut self) {
-        let x = self.cursor.x as i32;
+        let x = self.doc().cursor.x as i32;
         let new_x = match self.current_line() {
             Some(line) => {
                 let (new_x, new_byte_index) = line.cursor_and_byteindex(x);
@@ -289,36 +1774,127 @@ impl Editor {
                 new_x
             }
             None => {
-                error!("current_line is None: y {:?}", self.cursor.get_y());
+                error!("current_line is None: y {:?}", self.doc().cursor.get_y());
                 0
             }
         };
 
-        self.cursor.x = new_x;
+        self.doc_mut().cursor.x = new_x;
     }
 
+    /**
+     * 커서가 있는 창에서 줄 번호 영역을 뺀 본문 너비
+     */
+    fn text_area_width(&self) -> u16 {
+        let area = self.pane_areas()[self.focus];
+        area.w.saturating_sub(self.doc().cursor.get_x_offset())
+    }
+
+    /**
+     * 커서가 있는 창의 본문 높이
+     */
     fn edit_area_height(&self) -> u16 {
-        std::cmp::min(self.contents.len() as u16, screen_height() - 3)
+        self.pane_areas()[self.focus].h
+    }
+
+    /**
+     * 편집 영역. 메뉴 막대와 상태 표시줄(디버그 모드에서는 디버그 정보 줄까지)을 뺀다.
+     * 창을 나누면 이 영역을 나눠 쓴다.
+     */
+    fn edit_area(&self) -> Area {
+        let reserved = if self.debug { 3 } else { 2 };
+        Area {
+            x: 0,
+            y: 1,
+            w: screen_width() as u16,
+            h: screen_height().saturating_sub(reserved),
+        }
+    }
+
+    /**
+     * 창마다 차지하는 영역. 창 번호 순서
+     */
+    fn pane_areas(&self) -> Vec<Area> {
+        self.layout.areas(self.edit_area())
+    }
+
+    /**
+     * 창의 커서. 커서가 있는 창의 커서는 문서에 있다.
+     */
+    fn pane_cursor(&self, index: usize) -> &Cursor {
+        if index == self.focus {
+            &self.doc().cursor
+        } else {
+            &self.panes[index].cursor
+        }
+    }
+
+    fn last_line_index(&self) -> u16 {
+        self.doc().last_line_index()
     }
 
     // ================================================================================
     // 키 입력 핸들러
 
     fn handle_help(&mut self) {
-        let dialog = SimpleDialog::new();
-        dialog.draw(String::from(
+        self.show_message(
+            "about",
+            Severity::Info,
             "mdedit: simple text editor inspired by MS-DOS EDIT",
-        ));
-        self.popup = Some(dialog);
-        self.refresh(RefreshOption::None);
+            Buttons::Ok,
+        );
+        self.refresh(RefreshOption::Screen);
+    }
+
+    /**
+     * File > Exit. 바뀐 문서마다 그 문서로 바꿔서 저장할지 묻고, 모두 저장하거나 버렸으면 종료한다.
+     */
+    fn handle_exit(&mut self) {
+        let index = match self.documents.iter().position(|doc| doc.modified) {
+            Some(index) => index,
+            None => self.goodbye(),
+        };
+
+        self.switch_document(index);
+        let msg = format!("Save changes to {}?", self.doc().title());
+        self.show_message("exit", Severity::Warning, &msg, Buttons::YesNoCancel);
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn close_exit_confirm(&mut self, result: &DialogResult) {
+        match result.button {
+            Some("yes") => {
+                // 이름이 없으면 Save As 로 이름을 받은 다음 종료한다.
+                self.exit_after_save = self.doc().filename.is_none();
+                self.handle_save();
+                // 저장에 실패했으면 종료하지 않는다. 저장했으면 다음 문서를 묻는다.
+                if !self.doc().modified {
+                    self.handle_exit();
+                }
+            }
+            Some("no") => {
+                self.close_document();
+                self.handle_exit();
+            }
+            _ => {}
+        }
     }
 
     fn handle_menu(&mut self) {
         self.menu_bar.selected = Some(0);
-        self.menu_bar.draw(&self.screen, screen_width());
+        self.draw_menu_bar();
         self.refresh(RefreshOption::None);
     }
 
+    /**
+     * Alt+글자로 메뉴 열기 (ex: Alt+F 는 File 메뉴)
+     */
+    fn handle_menu_mnemonic(&mut self, ch: char) {
+        if self.menu_bar.open_by_mnemonic(ch) {
+            self.refresh(RefreshOption::Screen);
+        }
+    }
+
     /**
     입력된 키가 일반 문자일 경우 처리
 
@@ -326,54 +1902,134 @@ impl Editor {
     * `ch` - 입력된 문자
     */
     fn handle_input_char(&mut self, ch: char) {
+        let overwrite = self.overwrite;
+        let y = self.doc().cursor.get_y() as usize;
+        self.doc_mut().begin_edit(y, 1, true);
         if let Some(line) = self.current_line() {
-            line.insert(ch);
-            self.cursor
-                .move_right(screen_width() as u16, ch.width_cjk().unwrap_or(0) as u16);
+            if overwrite {
+                line.overwrite(ch);
+            } else {
+                line.insert(ch);
+            }
+            self.doc_mut().end_edit(1);
+            self.mark_modified();
+            let text_width = self.text_area_width();
+            self.doc_mut()
+                .cursor
+                .move_right(text_width, encoding::char_width(ch) as u16);
             self.refresh(RefreshOption::Line);
         }
     }
 
+    /**
+     * 삽입/겹쳐쓰기 모드 전환. 겹쳐쓰기 모드에서는 커서를 블록 모양으로 바꾼다.
+     */
+    fn handle_insertkey(&mut self) {
+        self.overwrite = !self.overwrite;
+        self.update_cursor_shape();
+        self.refresh(RefreshOption::None);
+    }
+
+    fn update_cursor_shape(&self) {
+        let shape = if self.overwrite {
+            CursorShape::Block
+        } else {
+            CursorShape::Line
+        };
+        check_result!(
+            queue!(&self.screen, SetCursorShape(shape)),
+            "Unable to set cursor shape"
+        );
+    }
+
+    /**
+     * 다음 탭 위치까지 공백을 넣는다. 탭 간격은 설정의 editor.tab_width.
+     */
+    fn handle_tab(&mut self) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.switch_pane();
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
+        let tab_width = self.config.tab_width;
+        for _ in 0..tab_width - self.doc().cursor.x % tab_width {
+            self.handle_input_char(' ');
+        }
+    }
+
     fn handle_enterkey(&mut self) {
+        if self.doc().hex.is_some() {
+            return;
+        }
+
         self.add_new_line();
         self.refresh(RefreshOption::None);
     }
 
     fn handle_backspace(&mut self) {
+        if self.doc().hex.is_some() {
+            // 바이트는 지우지 않고 덮어쓰기만 한다.
+            self.handle_leftkey();
+            return;
+        }
+
+        let y = self.doc().cursor.get_y() as usize;
+        self.doc_mut().begin_edit(y, 1, false);
         if let Some(line) = self.current_line() {
             let deleted = line.remove();
-            let char_width = match deleted.width_cjk() {
-                Some(width) => width as u16,
-                None => {
-                    error!("Unexpected operation on width_cjk");
-                    0
-                }
-            };
-            self.cursor.x -= char_width;
+            self.doc_mut().end_edit(1);
+            self.mark_modified();
+            let char_width = encoding::char_width(deleted) as u16;
+            self.doc_mut().cursor.x -= char_width;
             self.refresh(RefreshOption::Line);
         }
     }
 
     fn handle_upkey(&mut self) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.move_up();
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
         self.move_up();
         self.refresh(RefreshOption::None);
     }
 
     fn handle_downkey(&mut self) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.move_down();
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
         self.move_down();
         self.refresh(RefreshOption::None);
     }
 
     fn handle_leftkey(&mut self) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.move_left();
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
         if let Some(line) = self.current_line() {
             line.prev();
             let char_width = line.current_char_width() as u16;
-            self.cursor.move_left(char_width);
+            self.doc_mut().cursor.move_left(char_width);
             self.refresh(RefreshOption::None);
         }
     }
 
     fn handle_rightkey(&mut self) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.move_right();
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
         let char_width = match self.current_line() {
             Some(line) => {
                 let char_width = line.current_char_width() as u16;
@@ -383,42 +2039,270 @@ impl Editor {
             }
             None => 0,
         };
-        self.cursor.move_right(screen_width() as u16, char_width);
+        let text_width = self.text_area_width();
+        self.doc_mut().cursor.move_right(text_width, char_width);
         self.refresh(RefreshOption::None);
     }
 
-    fn handle_save(&self) {
-        match File::create("./test.txt") {
-            Ok(mut file) => {
-                for s in &self.contents {
-                    match writeln!(&mut file, "{}", s.get_buffer()) {
-                        Ok(_) => {}
-                        Err(e) => error!("Failed to write line {}", e),
-                    }
-                }
+    /**
+     * 본문을 클릭하면 그 위치로 커서를 옮긴다. 글자 경계에 맞추는 것은 update_cursor_x 에서 한다.
+     */
+    fn handle_click(&mut self, column: u16, row: u16) {
+        // 다른 창을 누르면 그 창으로 옮긴다. 구분선은 누르지 않은 것으로 한다.
+        let areas = self.pane_areas();
+        let index = match areas.iter().position(|area| area.contains(column, row)) {
+            Some(index) => index,
+            None => return,
+        };
+        self.focus_pane(index);
+        let (column, row) = (column - areas[index].x, row - areas[index].y);
+
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.click(column as usize, row as usize);
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
+        let line = self.doc().cursor.get_top() + row;
+        if line as usize >= self.doc().contents.len() {
+            return;
+        }
+
+        let cursor = &mut self.doc_mut().cursor;
+        cursor.set_y(line);
+        cursor.x = column.saturating_sub(cursor.get_x_offset());
+        self.update_cursor_x();
+        self.refresh(RefreshOption::None);
+    }
+
+    fn handle_wheel(&mut self, delta: i32) {
+        if let Some(hex) = &mut self.doc_mut().hex {
+            hex.scroll(delta);
+            self.refresh(RefreshOption::None);
+            return;
+        }
+
+        let old_top = self.doc().cursor.get_top();
+        let old_y = self.doc().cursor.get_y();
+        let (last, height) = (self.last_line_index(), self.edit_area_height());
+        self.doc_mut().cursor.scroll(delta, last, height);
+
+        if old_y != self.doc().cursor.get_y() {
+            self.update_cursor_x();
+        }
+        // 화면이 그대로여도 커서가 옮겨졌으면 커서와 상태 표시줄은 다시 그린다.
+        if old_top != self.doc().cursor.get_top() {
+            self.refresh(RefreshOption::Screen);
+        } else {
+            self.refresh(RefreshOption::None);
+        }
+    }
+
+    /**
+     * File > Save. 이름 없는 파일이면 Save As 로 이름을 먼저 받는다.
+     */
+    fn handle_save(&mut self) {
+        match self.doc().filename.clone() {
+            Some(filename) => self.save_to(&filename),
+            None => self.handle_save_as(),
+        }
+    }
+
+    /**
+     * File > Save As. 파일 이름을 입력받는다.
+     */
+    fn handle_save_as(&mut self) {
+        let current = self.doc().filename.as_deref().unwrap_or("");
+        let dialog = Dialog::new("save_as", "Save As", 50, 6)
+            .add("label", 2, 1, Label::new("File name:"))
+            .add("filename", 2, 2, TextInput::new(46, current))
+            .add("ok", 15, 4, Button::new("&OK"))
+            .add("cancel", 25, 4, Button::new("&Cancel"))
+            .default_button("ok");
+        self.open_dialog(dialog);
+        self.refresh(RefreshOption::Screen);
+    }
+
+    fn close_save_as(&mut self, result: &DialogResult) {
+        let filename = match (result.button, result.text("filename")) {
+            (Some("ok"), Some(name)) if !name.trim().is_empty() => String::from(name.trim()),
+            _ => {
+                self.exit_after_save = false;
+                self.close_after_save = false;
+                return;
             }
-            Err(e) => {
-                error!("Failed to open file to save: {:?}", e);
+        };
+
+        if self.doc().filename.as_ref() != Some(&filename) && Path::new(&filename).exists() {
+            let msg = format!("{} already exists. Replace it?", filename);
+            self.pending_save_as = Some(filename);
+            self.show_message("replace", Severity::Warning, &msg, Buttons::YesNo);
+            return;
+        }
+        self.save_and_maybe_exit(&filename);
+    }
+
+    fn close_replace_confirm(&mut self, result: &DialogResult) {
+        match (result.button, self.pending_save_as.take()) {
+            (Some("yes"), Some(filename)) => self.save_and_maybe_exit(&filename),
+            _ => {
+                self.exit_after_save = false;
+                self.close_after_save = false;
             }
         }
     }
-}
 
-fn read_char() -> Result<(KeyModifiers, KeyCode)> {
-    loop {
-        // rust 의 char 크기는 4바이트이므로 한글도 들어감.
-        if event::poll(std::time::Duration::from_millis(25))? {
-            if let Ok(Event::Key(KeyEvent {
-                code: c,
-                modifiers: m,
-            })) = event::read()
-            {
-                return Ok((m, c));
+    fn save_and_maybe_exit(&mut self, filename: &str) {
+        self.save_to(filename);
+        if std::mem::take(&mut self.exit_after_save) && !self.doc().modified {
+            self.handle_exit();
+        }
+        if std::mem::take(&mut self.close_after_save) && !self.doc().modified {
+            self.close_document();
+        }
+    }
+
+    /**
+     * 파일에 저장한다. 설정의 file.backup 에 따라 원래 파일을 백업한다.
+     * 다른 이름으로 저장했으면 그 파일을 편집하는 것으로 바꾼다.
+     */
+    fn save_to(&mut self, filename: &str) {
+        let path = Path::new(filename);
+        let data = match self.doc().file_data() {
+            Ok(data) => data,
+            Err(chars) => {
+                let msg = format!(
+                    "Cannot save {} as {}: {} cannot be represented. \
+                     Choose another encoding in File > Save with Encoding.",
+                    filename,
+                    self.doc().encoding.label(),
+                    char_list(&chars)
+                );
+                self.show_message("error", Severity::Error, &msg, Buttons::Ok);
+                self.refresh(RefreshOption::Screen);
+                return;
+            }
+        };
+        match save::save_file(path, &data, self.config.backup) {
+            Ok(backup) => {
+                if self.doc().filename.as_deref() != Some(filename) {
+                    // 스왑 파일도 새 이름을 따라간다.
+                    self.remove_swap();
+                    let doc = self.doc_mut();
+                    doc.filename = Some(String::from(filename));
+                    doc.swap_path = Some(swap::swap_path(Some(filename)));
+                    doc.read_only = false;
+                }
+                let doc = self.doc_mut();
+                doc.modified = false;
+                doc.swap_dirty = false;
+                if doc.hex.is_some() {
+                    // 텍스트로 돌아갈 때 저장한 바이트를 읽은 상태를 저장한 상태로 한다.
+                    doc.history.forget_saved();
+                } else {
+                    doc.history.mark_saved();
+                }
+                self.remove_swap();
+
+                let msg = match backup {
+                    Some(backup) => format!("Saved {} (backup: {})", filename, backup.display()),
+                    None => format!("Saved {}", filename),
+                };
+                self.notify(msg);
+                self.refresh(RefreshOption::None);
+            }
+            Err(err) => {
+                self.report_error(err);
+                self.refresh(RefreshOption::Screen);
             }
         }
     }
 }
 
+fn write_file(doc: &Document, path: &Path) -> std::result::Result<(), EditorError> {
+    // 파일의 인코딩으로 나타낼 수 없는 글자가 있으면 내용을 잃지 않도록 UTF-8 로 쓴다.
+    let data = doc.file_data().unwrap_or_else(|_| doc.text().into_bytes());
+    fs::write(path, data).map_err(|e| EditorError::new(Operation::Save, Some(path), e))
+}
+
+/**
+ * 터미널을 에디터를 실행하기 전 상태로 되돌린다. 여러 번 불러도 된다.
+ * 정상 종료, 입력 에러, 패닉 모든 경우에 불린다.
+ */
+pub fn restore_terminal() {
+    let mut screen = std::io::stdout();
+    check_result!(
+        execute!(
+            screen,
+            ResetColor,
+            SetCursorShape(CursorShape::Block),
+            crossterm::cursor::Show
+        ),
+        "Unable to restore cursor"
+    );
+    check_result!(
+        execute!(screen, DisableMouseCapture),
+        "Unable to disable mouse capture"
+    );
+    check_result!(
+        execute!(screen, terminal::LeaveAlternateScreen),
+        "Unable to leave alternate screen"
+    );
+    check_result!(terminal::disable_raw_mode(), "Unable to disable raw mode");
+}
+
+/**
+ * 열린 파일의 내용을 바이트 그대로 읽는다. 인코딩은 부르는 쪽에서 알아낸다.
+ */
+fn read_bytes(file: &mut fs::File) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    io::Read::read_to_end(file, &mut bytes)?;
+    Ok(bytes)
+}
+
+/**
+ * 메시지에 넣을 글자 목록. 많으면 앞의 몇 개만
+ */
+fn char_list(chars: &[char]) -> String {
+    const MAX: usize = 8;
+    let shown: Vec<String> = chars
+        .iter()
+        .take(MAX)
+        .map(|&ch| match encoding::escaped_byte(ch) {
+            Some(byte) => format!("\\x{:02X}", byte),
+            None => format!("'{}'", ch),
+        })
+        .collect();
+    if chars.len() > MAX {
+        format!("{} and {} more", shown.join(", "), chars.len() - MAX)
+    } else {
+        shown.join(", ")
+    }
+}
+
+/**
+ * 키보드/마우스 입력을 잠깐 기다린다. 그동안 입력이 없으면 None
+ */
+fn read_event() -> Result<Option<Event>> {
+    // rust 의 char 크기는 4바이트이므로 한글도 들어감.
+    if event::poll(Duration::from_millis(100))? {
+        return event::read().map(Some);
+    }
+    Ok(None)
+}
+
+/**
+ * 창의 배경색을 채울 너비. 줄은 넘겨준 너비에서 한 칸을 비워 두고 그리므로
+ * 오른쪽에 구분선이 있는 창은 구분선 자리까지 넘겨서 창 끝까지 채운다.
+ */
+fn fill_width(area: &Area) -> usize {
+    if ((area.x + area.w) as usize) < screen_width() {
+        area.w as usize + 1
+    } else {
+        area.w as usize
+    }
+}
+
 fn screen_width() -> usize {
     match size() {
         Ok((cols, _rows)) => cols as usize,
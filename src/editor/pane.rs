@@ -0,0 +1,15 @@
+use super::cursor::Cursor;
+
+/**
+편집 영역을 나눈 창 하나. 창마다 보여주는 문서와 커서, 스크롤 위치가 따로 있다.
+여러 창이 같은 문서를 보여주면 한 창에서 고친 내용이 다른 창에도 보인다.
+*/
+pub struct Pane {
+    /// 보여주는 문서의 인덱스
+    pub document: usize,
+    /// 커서가 없는 창의 커서 위치.
+    /// 커서가 있는 창의 커서는 문서(Document::cursor)에 있으므로 창을 옮길 때 서로 바꿔 넣는다.
+    pub cursor: Cursor,
+    /// 커서가 없는 창에서 View > Hex 의 맨 위에 보이는 줄. 커서처럼 창을 옮길 때 서로 바꿔 넣는다.
+    pub hex_top: usize,
+}
@@ -0,0 +1,177 @@
+use super::error::{EditorError, Operation};
+use crate::config::BackupPolicy;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/**
+저장할 내용을 임시 파일에 다 쓰고 fsync 한 다음 원래 파일 자리로 이름을 바꾼다.
+쓰다가 실패하거나 죽어도 원래 파일은 그대로 남는다.
+
+# Arguments
+* `path` - 저장할 파일. 심볼릭 링크면 링크가 가리키는 파일에 저장한다.
+* `data` - 저장할 내용 (줄 끝 문자까지 포함한 그대로)
+* `backup` - 원래 파일을 남기는 방식
+
+# Return
+* 만든 백업 파일 경로. 백업하지 않았으면 None
+*/
+pub fn save_file(
+    path: &Path,
+    data: &[u8],
+    backup: BackupPolicy,
+) -> Result<Option<PathBuf>, EditorError> {
+    let target = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let original = fs::metadata(&target).ok();
+
+    let backup_path = match (&original, backup_path(&target, backup, SystemTime::now())) {
+        (Some(_), Some(backup_path)) => {
+            fs::copy(&target, &backup_path)
+                .map_err(|e| EditorError::new(Operation::Backup, Some(&backup_path), e))?;
+            Some(backup_path)
+        }
+        _ => None,
+    };
+
+    let tmp = temp_path(&target);
+    let result = write_temp(&tmp, data, original.as_ref()).and_then(|_| fs::rename(&tmp, &target));
+    if let Err(e) = result {
+        let _ = fs::remove_file(&tmp);
+        return Err(EditorError::new(Operation::Save, Some(path), e));
+    }
+
+    sync_dir(&target);
+    Ok(backup_path)
+}
+
+fn write_temp(tmp: &Path, data: &[u8], original: Option<&fs::Metadata>) -> io::Result<()> {
+    let mut file = File::create(tmp)?;
+    file.write_all(data)?;
+    if let Some(metadata) = original {
+        file.set_permissions(metadata.permissions())?;
+    }
+    file.sync_all()
+}
+
+/**
+ * 같은 디렉터리에 만드는 임시 파일. 이름 바꾸기가 원자적이려면 같은 파일 시스템에 있어야 한다.
+ */
+fn temp_path(target: &Path) -> PathBuf {
+    let name = target
+        .file_name()
+        .map(|s| s.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    target.with_file_name(format!(".{}.mdedit-{}.tmp", name, std::process::id()))
+}
+
+/**
+ * 이름 바꾸기가 디스크에 기록되도록 디렉터리도 fsync 한다. 지원하지 않는 플랫폼도 있으므로 실패는 무시한다.
+ */
+fn sync_dir(target: &Path) {
+    if let Some(dir) = target.parent() {
+        let dir = if dir.as_os_str().is_empty() {
+            Path::new(".")
+        } else {
+            dir
+        };
+        if let Ok(dir) = File::open(dir) {
+            let _ = dir.sync_all();
+        }
+    }
+}
+
+/**
+ * 백업 파일 경로. Simple 은 `file~`, Timestamp 는 `file.20240101-120000~` (UTC)
+ */
+fn backup_path(target: &Path, policy: BackupPolicy, now: SystemTime) -> Option<PathBuf> {
+    let name = target.file_name()?.to_string_lossy().into_owned();
+    match policy {
+        BackupPolicy::None => None,
+        BackupPolicy::Simple => Some(target.with_file_name(format!("{}~", name))),
+        BackupPolicy::Timestamp => {
+            let secs = now.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs());
+            Some(target.with_file_name(format!("{}.{}~", name, timestamp(secs))))
+        }
+    }
+}
+
+/**
+ * UNIX 시간을 "YYYYMMDD-HHMMSS" (UTC) 로 바꾼다.
+ */
+fn timestamp(secs: u64) -> String {
+    let days = (secs / 86400) as i64;
+    let rem = secs % 86400;
+
+    // 1970-01-01 부터의 날 수를 그레고리력 날짜로 바꾼다. (Howard Hinnant 의 civil_from_days)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}{:02}{:02}-{:02}{:02}{:02}",
+        year,
+        month,
+        day,
+        rem / 3600,
+        rem % 3600 / 60,
+        rem % 60
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_backup_path() {
+        let target = Path::new("/tmp/memo.md");
+        let now = UNIX_EPOCH + Duration::from_secs(1_704_110_400);
+        assert_eq!(backup_path(target, BackupPolicy::None, now), None);
+        assert_eq!(
+            backup_path(target, BackupPolicy::Simple, now),
+            Some(PathBuf::from("/tmp/memo.md~"))
+        );
+        assert_eq!(
+            backup_path(target, BackupPolicy::Timestamp, now),
+            Some(PathBuf::from("/tmp/memo.md.20240101-120000~"))
+        );
+        assert_eq!(timestamp(0), "19700101-000000");
+        assert_eq!(timestamp(951_782_399), "20000228-235959");
+        assert_eq!(timestamp(951_868_800), "20000301-000000");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_save_file() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = std::env::temp_dir().join(format!("mdedit-save-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("memo.md");
+        fs::write(&path, "old\n").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+
+        let data = "new\r\n줄".as_bytes();
+        let backup = save_file(&path, data, BackupPolicy::Simple).unwrap();
+        assert_eq!(backup, Some(dir.join("memo.md~")));
+        assert_eq!(fs::read(&path).unwrap(), data);
+        assert_eq!(fs::read_to_string(dir.join("memo.md~")).unwrap(), "old\n");
+        let mode = fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o640);
+
+        // 디렉터리가 없으면 원래 파일을 건드리지 않고 실패한다.
+        let err = save_file(&dir.join("none/x.md"), b"a\n", BackupPolicy::None);
+        assert_eq!(err.unwrap_err().op, Operation::Save);
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,236 @@
+use super::line_ending::LineEnding;
+use std::env;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+/// 스왑 파일 첫 줄. 형식이 바뀌면 번호를 올린다.
+const MAGIC: &str = "mdedit-swap 2";
+/// 이름 없는 파일의 스왑 파일 이름 앞부분
+const UNTITLED_PREFIX: &str = "untitled-";
+const EXTENSION: &str = "swp";
+
+/**
+읽어들인 스왑 파일. 복구한 뒤에 저장해도 파일이 바뀌지 않도록 줄마다 줄 끝 문자도 적어 둔다.
+
+```text
+mdedit-swap 2
+pid 1234
+final-newline no
+CRLF 첫 줄
+CRLF 마지막 줄
+```
+*/
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SwapFile {
+    /// 스왑 파일을 쓴 에디터의 프로세스 id
+    pub pid: u32,
+    /// 마지막 줄 뒤에도 줄 끝 문자를 쓰는지 여부
+    pub final_newline: bool,
+    /// 줄 내용과 그 줄의 줄 끝 문자
+    pub lines: Vec<(String, LineEnding)>,
+}
+
+/**
+스왑 파일 위치. 파일 옆에 `.<파일 이름>.swp` 로 두고,
+이름 없는 파일은 상태 디렉터리에 `untitled-<pid>.swp` 로 둔다. (untitled_swap_path)
+*/
+pub fn swap_path(filename: Option<&str>) -> PathBuf {
+    match filename {
+        Some(name) => {
+            let path = Path::new(name);
+            let file_name = path
+                .file_name()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            path.with_file_name(format!(".{}.{}", file_name, EXTENSION))
+        }
+        None => untitled_swap_path(0),
+    }
+}
+
+/**
+ * 이름 없는 문서의 스왑 파일 위치. 이름 없는 문서가 여러 개면 두 번째부터 `untitled-<pid>-<번호>.swp`
+ */
+pub fn untitled_swap_path(number: usize) -> PathBuf {
+    let name = match number {
+        0 => format!("{}{}.{}", UNTITLED_PREFIX, std::process::id(), EXTENSION),
+        n => format!(
+            "{}{}-{}.{}",
+            UNTITLED_PREFIX,
+            std::process::id(),
+            n,
+            EXTENSION
+        ),
+    };
+    state_dir().join(name)
+}
+
+/**
+ * 이름 없는 파일의 스왑 파일을 두는 곳. $XDG_STATE_HOME/mdedit, 없으면 ~/.local/state/mdedit
+ */
+fn state_dir() -> PathBuf {
+    let base = match env::var_os("XDG_STATE_HOME") {
+        Some(dir) if !dir.is_empty() => PathBuf::from(dir),
+        _ => match env::var_os("HOME") {
+            Some(home) => PathBuf::from(home).join(".local").join("state"),
+            None => env::temp_dir(),
+        },
+    };
+    base.join("mdedit")
+}
+
+/**
+ * 스왑 파일을 쓴다. 쓰다가 죽어도 이전 스왑 파일이 남도록 임시 파일에 쓰고 이름을 바꾼다.
+ */
+pub fn write<'a>(
+    path: &Path,
+    lines: impl Iterator<Item = (&'a str, LineEnding)>,
+    final_newline: bool,
+) -> io::Result<()> {
+    if let Some(dir) = path.parent() {
+        if !dir.as_os_str().is_empty() {
+            fs::create_dir_all(dir)?;
+        }
+    }
+
+    let tmp = path.with_extension(format!("{}.tmp", EXTENSION));
+    let mut writer = BufWriter::new(File::create(&tmp)?);
+    writeln!(writer, "{}", MAGIC)?;
+    writeln!(writer, "pid {}", std::process::id())?;
+    let final_newline = if final_newline { "yes" } else { "no" };
+    writeln!(writer, "final-newline {}", final_newline)?;
+    for (line, ending) in lines {
+        writeln!(writer, "{} {}", ending.label(), line)?;
+    }
+    let file = writer.into_inner().map_err(|e| e.into_error())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)
+}
+
+pub fn read(path: &Path) -> io::Result<SwapFile> {
+    let mut lines = BufReader::new(File::open(path)?).lines();
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "not an mdedit swap file");
+
+    if lines.next().transpose()?.as_deref() != Some(MAGIC) {
+        return Err(invalid());
+    }
+    let pid = lines
+        .next()
+        .transpose()?
+        .and_then(|line| line.strip_prefix("pid ").and_then(|n| n.parse().ok()))
+        .ok_or_else(invalid)?;
+    let final_newline = match lines.next().transpose()?.as_deref() {
+        Some("final-newline yes") => true,
+        Some("final-newline no") => false,
+        _ => return Err(invalid()),
+    };
+
+    let mut contents = Vec::new();
+    for line in lines {
+        let line = line?;
+        let (label, text) = line.split_once(' ').unwrap_or((&line, ""));
+        let ending = LineEnding::ALL
+            .iter()
+            .copied()
+            .find(|ending| ending.label() == label)
+            .ok_or_else(invalid)?;
+        contents.push((String::from(text), ending));
+    }
+
+    Ok(SwapFile {
+        pid,
+        final_newline,
+        lines: contents,
+    })
+}
+
+/**
+ * 스왑 파일을 지운다. 이미 없으면 성공으로 본다.
+ */
+pub fn remove(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+        _ => Ok(()),
+    }
+}
+
+/**
+ * 스왑 파일을 쓴 에디터가 아직 실행 중인지 여부. 알 수 없으면 false
+ */
+pub fn process_running(pid: u32) -> bool {
+    if cfg!(target_os = "linux") {
+        Path::new("/proc").join(pid.to_string()).exists()
+    } else {
+        false
+    }
+}
+
+/**
+ * 죽은 에디터가 남긴 이름 없는 파일의 스왑 파일을 찾는다.
+ */
+pub fn find_stale_untitled() -> Option<PathBuf> {
+    let entries = fs::read_dir(state_dir()).ok()?;
+    entries
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| {
+            let pid = path
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| name.strip_prefix(UNTITLED_PREFIX))
+                .and_then(|name| name.strip_suffix(&format!(".{}", EXTENSION)))
+                .and_then(|name| name.split('-').next())
+                .and_then(|pid| pid.parse::<u32>().ok());
+            match pid {
+                Some(pid) => pid != std::process::id() && !process_running(pid),
+                None => false,
+            }
+        })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_swap_path() {
+        assert_eq!(
+            swap_path(Some("docs/memo.md")),
+            PathBuf::from("docs/.memo.md.swp")
+        );
+        assert_eq!(swap_path(Some("memo.md")), PathBuf::from(".memo.md.swp"));
+        let untitled = swap_path(None);
+        assert!(untitled.ends_with(format!("untitled-{}.swp", std::process::id())));
+        let second = untitled_swap_path(2);
+        assert!(second.ends_with(format!("untitled-{}-2.swp", std::process::id())));
+    }
+
+    #[test]
+    fn test_write_read() {
+        let dir = env::temp_dir().join(format!("mdedit-swap-test-{}", std::process::id()));
+        let path = dir.join(".memo.md.swp");
+
+        let lines = [
+            ("# 제목", LineEnding::CrLf),
+            ("", LineEnding::Lf),
+            ("본문 ", LineEnding::Cr),
+        ];
+        write(&path, lines.iter().copied(), false).unwrap();
+        let swap = read(&path).unwrap();
+        assert_eq!(swap.pid, std::process::id());
+        assert!(!swap.final_newline);
+        let expected: Vec<(String, LineEnding)> = lines
+            .iter()
+            .map(|(line, ending)| (String::from(*line), *ending))
+            .collect();
+        assert_eq!(swap.lines, expected);
+        assert!(process_running(swap.pid) || !cfg!(target_os = "linux"));
+
+        fs::write(&path, "hello\n").unwrap();
+        assert_eq!(read(&path).unwrap_err().kind(), io::ErrorKind::InvalidData);
+
+        remove(&path).unwrap();
+        remove(&path).unwrap();
+        fs::remove_dir(&dir).unwrap();
+    }
+}
@@ -0,0 +1,66 @@
+use super::{move_to, Widget, WidgetEvent};
+use crate::editor::ui::menu_bar::{draw_label, mnemonic_char, parse_mnemonic};
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * `< OK >` 모양의 버튼. Enter, Space, 클릭, 단축 글자로 누른다.
+ */
+pub struct Button {
+    label: String,
+    mnemonic: Option<usize>,
+}
+
+impl Button {
+    /**
+    # Arguments
+    * `label` - 버튼 이름. 메뉴처럼 '&' 바로 뒤의 글자가 단축 글자가 된다. (ex: "&Yes")
+    */
+    pub fn new(label: &str) -> Button {
+        let (label, mnemonic) = parse_mnemonic(label);
+        Button { label, mnemonic }
+    }
+}
+
+impl Widget for Button {
+    fn draw(&self, x: u16, y: u16, focused: bool, theme: &Theme) {
+        if focused {
+            set_color(theme.selection.fg, theme.selection.bg);
+        }
+        move_to(x, y);
+        print!("< ");
+        draw_label(&self.label, self.mnemonic);
+        print!(" >");
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.label.width_cjk() as u16 + 4, 1)
+    }
+
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> WidgetEvent {
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Enter) | (KeyModifiers::NONE, KeyCode::Char(' ')) => {
+                WidgetEvent::Pressed
+            }
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    fn handle_click(&mut self, _x: u16, _y: u16) -> WidgetEvent {
+        WidgetEvent::Pressed
+    }
+
+    fn cursor(&self) -> (u16, u16) {
+        (2, 0)
+    }
+
+    fn is_button(&self) -> bool {
+        true
+    }
+
+    fn mnemonic(&self) -> Option<char> {
+        mnemonic_char(&self.label, self.mnemonic)
+    }
+}
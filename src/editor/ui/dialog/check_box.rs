@@ -0,0 +1,57 @@
+use super::{move_to, Widget, WidgetEvent, WidgetValue};
+use crate::editor::ui::theme::Theme;
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * `[X] 설명` 모양의 체크 박스. Space 나 클릭으로 바꾼다.
+ */
+pub struct CheckBox {
+    label: String,
+    checked: bool,
+}
+
+impl CheckBox {
+    pub fn new(label: &str, checked: bool) -> CheckBox {
+        CheckBox {
+            label: String::from(label),
+            checked,
+        }
+    }
+
+    fn toggle(&mut self) -> WidgetEvent {
+        self.checked = !self.checked;
+        WidgetEvent::Changed
+    }
+}
+
+impl Widget for CheckBox {
+    fn draw(&self, x: u16, y: u16, _focused: bool, _theme: &Theme) {
+        move_to(x, y);
+        let mark = if self.checked { 'X' } else { ' ' };
+        print!("[{}] {}", mark, self.label);
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.label.width_cjk() as u16 + 4, 1)
+    }
+
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> WidgetEvent {
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Char(' ')) => self.toggle(),
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    fn handle_click(&mut self, _x: u16, _y: u16) -> WidgetEvent {
+        self.toggle()
+    }
+
+    fn value(&self) -> WidgetValue {
+        WidgetValue::Checked(self.checked)
+    }
+
+    fn cursor(&self) -> (u16, u16) {
+        (1, 0)
+    }
+}
@@ -0,0 +1,36 @@
+use super::{move_to, Widget};
+use crate::editor::ui::theme::Theme;
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * 글자만 보여주는 위젯. '\n' 으로 여러 줄을 쓸 수 있고, 포커스를 받지 않는다.
+ */
+pub struct Label {
+    lines: Vec<String>,
+}
+
+impl Label {
+    pub fn new(text: &str) -> Label {
+        Label {
+            lines: text.split('\n').map(String::from).collect(),
+        }
+    }
+}
+
+impl Widget for Label {
+    fn draw(&self, x: u16, y: u16, _focused: bool, _theme: &Theme) {
+        for (i, line) in self.lines.iter().enumerate() {
+            move_to(x, y + i as u16);
+            print!("{}", line);
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        let width = self.lines.iter().map(|s| s.width_cjk()).max().unwrap_or(0);
+        (width as u16, self.lines.len() as u16)
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,161 @@
+use super::{fit, move_to, Widget, WidgetEvent, WidgetValue};
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::event::{KeyCode, KeyModifiers};
+
+/**
+ * 항목 목록에서 하나를 고르는 위젯. 항목이 높이보다 많으면 스크롤하고 오른쪽에 스크롤 바를 그린다.
+ */
+pub struct ListBox {
+    items: Vec<String>,
+    selected: Option<usize>,
+    /// 맨 위에 보이는 항목
+    top: usize,
+    w: u16,
+    h: u16,
+}
+
+impl ListBox {
+    pub fn new(items: &[&str], selected: usize, w: u16, h: u16) -> ListBox {
+        let mut list = ListBox {
+            items: items.iter().map(|s| String::from(*s)).collect(),
+            selected: None,
+            top: 0,
+            w,
+            h: h.max(1),
+        };
+        list.select(selected);
+        list
+    }
+
+    fn scrollable(&self) -> bool {
+        self.items.len() > self.h as usize
+    }
+
+    /**
+     * 항목을 고르고, 고른 항목이 보이도록 스크롤한다. 범위를 넘으면 끝 항목을 고른다.
+     */
+    fn select(&mut self, idx: usize) -> WidgetEvent {
+        if self.items.is_empty() {
+            return WidgetEvent::Handled;
+        }
+
+        let idx = idx.min(self.items.len() - 1);
+        let height = self.h as usize;
+        if idx < self.top {
+            self.top = idx;
+        } else if idx >= self.top + height {
+            self.top = idx + 1 - height;
+        }
+
+        if self.selected == Some(idx) {
+            return WidgetEvent::Handled;
+        }
+        self.selected = Some(idx);
+        WidgetEvent::Changed
+    }
+}
+
+impl Widget for ListBox {
+    fn draw(&self, x: u16, y: u16, _focused: bool, theme: &Theme) {
+        let scrollable = self.scrollable();
+        let text_width = if scrollable { self.w - 1 } else { self.w };
+
+        for row in 0..self.h {
+            let idx = self.top + row as usize;
+            if Some(idx) == self.selected {
+                set_color(theme.selection.fg, theme.selection.bg);
+            } else {
+                set_color(theme.dialog.fg, theme.dialog.bg);
+            }
+            move_to(x, y + row);
+            let item = self.items.get(idx).map_or("", |s| s.as_str());
+            print!("{}", fit(&format!(" {}", item), text_width as usize));
+        }
+
+        if scrollable {
+            set_color(theme.dialog.fg, theme.dialog.bg);
+            let last = self.items.len() - 1;
+            let thumb = self.selected.unwrap_or(0) * (self.h as usize - 1) / last.max(1);
+            for row in 0..self.h {
+                move_to(x + text_width, y + row);
+                print!("{}", if row as usize == thumb { '█' } else { '░' });
+            }
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.w, self.h)
+    }
+
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> WidgetEvent {
+        let selected = self.selected.unwrap_or(0);
+        let page = self.h as usize;
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Up) => self.select(selected.saturating_sub(1)),
+            (KeyModifiers::NONE, KeyCode::Down) => self.select(selected + 1),
+            (KeyModifiers::NONE, KeyCode::PageUp) => self.select(selected.saturating_sub(page)),
+            (KeyModifiers::NONE, KeyCode::PageDown) => self.select(selected + page),
+            (KeyModifiers::NONE, KeyCode::Home) => self.select(0),
+            (KeyModifiers::NONE, KeyCode::End) => self.select(usize::MAX),
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    fn handle_click(&mut self, _x: u16, y: u16) -> WidgetEvent {
+        let idx = self.top + y as usize;
+        if idx < self.items.len() {
+            self.select(idx)
+        } else {
+            WidgetEvent::Handled
+        }
+    }
+
+    /**
+     * 고른 항목은 그대로 두고 보이는 범위만 옮긴다.
+     */
+    fn handle_scroll(&mut self, delta: i32) -> WidgetEvent {
+        let max_top = self.items.len().saturating_sub(self.h as usize);
+        self.top = (self.top as i64 + delta as i64).clamp(0, max_top as i64) as usize;
+        WidgetEvent::Handled
+    }
+
+    fn value(&self) -> WidgetValue {
+        WidgetValue::Selected(self.selected)
+    }
+
+    fn cursor(&self) -> (u16, u16) {
+        let row = self.selected.unwrap_or(0).saturating_sub(self.top);
+        (0, (row as u16).min(self.h - 1))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_scroll() {
+        let items = ["a", "b", "c", "d", "e", "f"];
+        let mut list = ListBox::new(&items, 0, 10, 3);
+        assert_eq!(
+            list.handle_keyinput(KeyModifiers::NONE, KeyCode::Up),
+            WidgetEvent::Handled
+        );
+
+        list.handle_keyinput(KeyModifiers::NONE, KeyCode::PageDown);
+        assert_eq!((list.selected, list.top), (Some(3), 1));
+        list.handle_keyinput(KeyModifiers::NONE, KeyCode::End);
+        assert_eq!((list.selected, list.top), (Some(5), 3));
+        list.handle_click(0, 0);
+        assert_eq!(list.value(), WidgetValue::Selected(Some(3)));
+
+        list.handle_scroll(-5);
+        assert_eq!((list.selected, list.top), (Some(3), 0));
+        list.handle_scroll(5);
+        assert_eq!(list.top, 3);
+
+        let empty = ListBox::new(&[], 0, 10, 3);
+        assert_eq!(empty.value(), WidgetValue::Selected(None));
+    }
+}
@@ -0,0 +1,230 @@
+use super::{move_to, Button, Dialog, Label, Widget};
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// 메시지 한 줄의 최대/최소 너비
+const MAX_TEXT_WIDTH: u16 = 60;
+const MIN_TEXT_WIDTH: u16 = 20;
+/// 메시지 왼쪽의 테두리, 아이콘, 여백과 오른쪽의 여백, 테두리
+const TEXT_X: u16 = 6;
+const EXTRA_WIDTH: u16 = TEXT_X + 2;
+
+/**
+ * 메시지의 심각도. 제목과 아이콘 색이 달라진다.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl Severity {
+    fn title(self) -> &'static str {
+        match self {
+            Severity::Info => "Information",
+            Severity::Warning => "Warning",
+            Severity::Error => "Error",
+        }
+    }
+}
+
+/**
+ * 메시지 상자의 버튼 조합. 버튼 id 는 "ok", "cancel", "yes", "no"
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[allow(dead_code)] // 아직 쓰지 않는 조합도 있다.
+pub enum Buttons {
+    Ok,
+    OkCancel,
+    YesNo,
+    YesNoCancel,
+    /// (버튼 id, 버튼 이름) 목록. 첫 번째가 기본 버튼
+    Custom(&'static [(&'static str, &'static str)]),
+}
+
+impl Buttons {
+    /**
+     * (버튼 id, 버튼 이름). 첫 번째가 기본 버튼
+     */
+    fn list(self) -> &'static [(&'static str, &'static str)] {
+        match self {
+            Buttons::Ok => &[("ok", "&OK")],
+            Buttons::OkCancel => &[("ok", "&OK"), ("cancel", "&Cancel")],
+            Buttons::YesNo => &[("yes", "&Yes"), ("no", "&No")],
+            Buttons::YesNoCancel => &[("yes", "&Yes"), ("no", "&No"), ("cancel", "&Cancel")],
+            Buttons::Custom(list) => list,
+        }
+    }
+}
+
+/**
+ * 메시지 왼쪽에 심각도 색으로 그리는 아이콘
+ */
+struct SeverityIcon(Severity);
+
+impl Widget for SeverityIcon {
+    fn draw(&self, x: u16, y: u16, _focused: bool, theme: &Theme) {
+        let (colors, icon) = match self.0 {
+            Severity::Info => (theme.info, " i "),
+            Severity::Warning => (theme.warning, " ! "),
+            Severity::Error => (theme.error, " x "),
+        };
+        set_color(colors.fg, colors.bg);
+        move_to(x, y);
+        print!("{}", icon);
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (3, 1)
+    }
+
+    fn focusable(&self) -> bool {
+        false
+    }
+}
+
+/**
+메시지와 버튼만 있는 대화상자. 긴 메시지는 단어 단위로 줄바꿈한다.
+Esc 로 닫으면 결과의 button 은 None 이다.
+
+# Arguments
+* `id` - 결과를 받을 때 구분할 대화상자 id
+* `msg` - 메시지. '\n' 으로 줄을 나눌 수 있다.
+* `screen_width` - 화면 너비. 메시지 너비를 정할 때 쓴다.
+*/
+pub fn message_box(
+    id: &'static str,
+    severity: Severity,
+    msg: &str,
+    buttons: Buttons,
+    screen_width: u16,
+) -> Dialog {
+    let max_width = screen_width
+        .saturating_sub(EXTRA_WIDTH + 4)
+        .clamp(MIN_TEXT_WIDTH, MAX_TEXT_WIDTH);
+    let lines = wrap_text(msg, max_width as usize);
+    let text_width = lines.iter().map(|line| line.width_cjk()).max().unwrap_or(0) as u16;
+
+    let buttons: Vec<(&'static str, Button)> = buttons
+        .list()
+        .iter()
+        .map(|(id, label)| (*id, Button::new(label)))
+        .collect();
+    // 버튼 사이는 두 칸 띄운다.
+    let buttons_width = buttons
+        .iter()
+        .map(|(_, button)| button.size().0 + 2)
+        .sum::<u16>()
+        - 2;
+
+    let w = (text_width + EXTRA_WIDTH)
+        .max(buttons_width + 4)
+        .max(severity.title().width_cjk() as u16 + 6);
+    let h = lines.len() as u16 + 4;
+
+    let mut dialog = Dialog::new(id, severity.title(), w, h)
+        .add("icon", 2, 1, SeverityIcon(severity))
+        .add("message", TEXT_X, 1, Label::new(&lines.join("\n")))
+        .default_button(buttons[0].0);
+
+    let mut x = (w - buttons_width) / 2;
+    for (id, button) in buttons {
+        let width = button.size().0;
+        dialog = dialog.add(id, x, h - 2, button);
+        x += width + 2;
+    }
+    dialog
+}
+
+/**
+ * 글자를 너비에 맞춰 단어 단위로 줄바꿈한다. 너비보다 긴 단어는 글자 단위로 자른다.
+ */
+fn wrap_text(text: &str, width: usize) -> Vec<String> {
+    let mut lines = Vec::new();
+
+    for paragraph in text.split('\n') {
+        let mut line = String::new();
+        for word in paragraph.split(' ').filter(|word| !word.is_empty()) {
+            let line_width = line.width_cjk();
+            let word_width = word.width_cjk();
+            if line.is_empty() && word_width <= width {
+                line.push_str(word);
+            } else if line_width + 1 + word_width <= width {
+                line.push(' ');
+                line.push_str(word);
+            } else if word_width <= width {
+                lines.push(std::mem::replace(&mut line, String::from(word)));
+            } else {
+                if !line.is_empty() {
+                    lines.push(std::mem::take(&mut line));
+                }
+                for c in word.chars() {
+                    if line.width_cjk() + c.width_cjk().unwrap_or(0) > width {
+                        lines.push(std::mem::take(&mut line));
+                    }
+                    line.push(c);
+                }
+            }
+        }
+        lines.push(line);
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::editor::ui::dialog::DialogEvent;
+    use crossterm::event::{KeyCode, KeyModifiers};
+
+    #[test]
+    fn test_wrap_text() {
+        assert_eq!(
+            wrap_text("the quick brown fox", 10),
+            vec!["the quick", "brown fox"]
+        );
+        assert_eq!(wrap_text("a\n\nb", 10), vec!["a", "", "b"]);
+        assert_eq!(
+            wrap_text("path: /very/long/file/name", 10),
+            vec!["path:", "/very/long", "/file/name"]
+        );
+        // 한글은 두 칸
+        assert_eq!(wrap_text("한글 두칸씩", 6), vec!["한글", "두칸씩"]);
+    }
+
+    #[test]
+    fn test_message_box() {
+        let closed_with = |event: DialogEvent| match event {
+            DialogEvent::Closed(result) => result.button,
+            event => panic!("unexpected {:?}", event),
+        };
+
+        let mut dialog = message_box("q", Severity::Warning, "Save?", Buttons::YesNoCancel, 80);
+        assert_eq!(
+            closed_with(dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('n'))),
+            Some("no")
+        );
+        assert_eq!(
+            closed_with(dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter)),
+            Some("yes")
+        );
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Right);
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Right);
+        assert_eq!(
+            closed_with(dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter)),
+            Some("cancel")
+        );
+        assert_eq!(
+            closed_with(dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Esc)),
+            None
+        );
+
+        let long = "word ".repeat(40);
+        let dialog = message_box("m", Severity::Error, &long, Buttons::Ok, 80);
+        assert_eq!(dialog.w, MAX_TEXT_WIDTH + EXTRA_WIDTH - 1);
+        assert!(dialog.h > 4);
+    }
+}
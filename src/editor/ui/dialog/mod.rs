@@ -0,0 +1,506 @@
+mod button;
+mod check_box;
+mod label;
+mod list_box;
+mod message_box;
+mod radio_group;
+mod text_input;
+
+pub use button::Button;
+pub use check_box::CheckBox;
+pub use label::Label;
+pub use list_box::ListBox;
+pub use message_box::{message_box, Buttons, Severity};
+pub use radio_group::RadioGroup;
+pub use text_input::TextInput;
+
+use crate::editor::ui::rect::Rect;
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use crossterm::style::ResetColor;
+use crossterm::{cursor, queue};
+use log::error;
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/**
+ * 위젯이 키/마우스 입력을 처리한 결과
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WidgetEvent {
+    /// 처리하지 않음. 대화상자가 대신 처리할 수 있다.
+    Ignored,
+    /// 처리했지만 값은 그대로
+    Handled,
+    /// 값이 바뀜
+    Changed,
+    /// 버튼을 누름
+    Pressed,
+}
+
+/**
+ * 위젯의 현재 값
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WidgetValue {
+    None,
+    Text(String),
+    Checked(bool),
+    Selected(Option<usize>),
+}
+
+/**
+ * 대화상자 안에 놓이는 부품. 위치는 대화상자 왼쪽 위 기준이고, 입력 좌표는 위젯 왼쪽 위 기준이다.
+ */
+pub trait Widget {
+    fn draw(&self, x: u16, y: u16, focused: bool, theme: &Theme);
+
+    /// (너비, 높이)
+    fn size(&self) -> (u16, u16);
+
+    /// Tab 으로 옮겨 갈 수 있는지 여부
+    fn focusable(&self) -> bool {
+        true
+    }
+
+    fn handle_keyinput(&mut self, _modifier: KeyModifiers, _code: KeyCode) -> WidgetEvent {
+        WidgetEvent::Ignored
+    }
+
+    fn handle_click(&mut self, _x: u16, _y: u16) -> WidgetEvent {
+        WidgetEvent::Ignored
+    }
+
+    /// 마우스 휠. 음수면 위로
+    fn handle_scroll(&mut self, _delta: i32) -> WidgetEvent {
+        WidgetEvent::Ignored
+    }
+
+    fn value(&self) -> WidgetValue {
+        WidgetValue::None
+    }
+
+    /// 포커스를 받았을 때 터미널 커서를 놓을 위치
+    fn cursor(&self) -> (u16, u16) {
+        (0, 0)
+    }
+
+    /// Enter 로 누를 수 있는 버튼인지 여부
+    fn is_button(&self) -> bool {
+        false
+    }
+
+    /// 누르면 대화상자를 닫는 단축 글자 (소문자)
+    fn mnemonic(&self) -> Option<char> {
+        None
+    }
+}
+
+struct Entry {
+    id: &'static str,
+    x: u16,
+    y: u16,
+    widget: Box<dyn Widget>,
+}
+
+/**
+ * 대화상자의 키/마우스 입력을 처리한 결과
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DialogEvent {
+    None,
+    /// 위젯 값이 바뀜. 값은 위젯 id
+    Changed(&'static str),
+    /// 대화상자를 닫아야 함
+    Closed(DialogResult),
+}
+
+/**
+ * 대화상자를 닫을 때 에디터에 돌려주는 결과
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DialogResult {
+    /// 누른 버튼의 id. Esc 로 닫았으면 None
+    pub button: Option<&'static str>,
+    values: Vec<(&'static str, WidgetValue)>,
+}
+
+impl DialogResult {
+    pub fn value(&self, id: &str) -> &WidgetValue {
+        self.values
+            .iter()
+            .find(|(widget_id, _)| *widget_id == id)
+            .map(|(_, value)| value)
+            .unwrap_or(&WidgetValue::None)
+    }
+
+    pub fn text(&self, id: &str) -> Option<&str> {
+        match self.value(id) {
+            WidgetValue::Text(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn checked(&self, id: &str) -> Option<bool> {
+        match self.value(id) {
+            WidgetValue::Checked(checked) => Some(*checked),
+            _ => None,
+        }
+    }
+
+    pub fn selected(&self, id: &str) -> Option<usize> {
+        match self.value(id) {
+            WidgetValue::Selected(selected) => *selected,
+            _ => None,
+        }
+    }
+}
+
+/**
+위젯을 담는 대화상자. Tab/Shift+Tab 으로 포커스를 옮기고, Enter 는 기본 버튼, Esc 는 취소.
+
+```ignore
+let dialog = Dialog::new("find", "Find", 40, 7)
+    .add("what", 2, 1, TextInput::new(30, ""))
+    .add("ok", 10, 4, Button::new("OK"))
+    .default_button("ok");
+```
+*/
+pub struct Dialog {
+    /// 결과를 받을 때 어떤 대화상자인지 구분하는 id
+    pub id: &'static str,
+    title: String,
+    x: u16,
+    y: u16,
+    w: u16,
+    h: u16,
+    entries: Vec<Entry>,
+    focus: usize,
+    default_button: Option<&'static str>,
+}
+
+impl Dialog {
+    pub fn new(id: &'static str, title: &str, w: u16, h: u16) -> Dialog {
+        Dialog {
+            id,
+            title: String::from(title),
+            x: 0,
+            y: 0,
+            w,
+            h,
+            entries: Vec::new(),
+            focus: 0,
+            default_button: None,
+        }
+    }
+
+    /**
+     * 위젯을 추가한다. 처음 추가한 포커스 가능 위젯이 처음 포커스를 받는다.
+     */
+    pub fn add(
+        mut self,
+        id: &'static str,
+        x: u16,
+        y: u16,
+        widget: impl Widget + 'static,
+    ) -> Dialog {
+        self.entries.push(Entry {
+            id,
+            x,
+            y,
+            widget: Box::new(widget),
+        });
+        if !self.entries[self.focus].widget.focusable() {
+            self.focus = self.entries.len() - 1;
+        }
+        self
+    }
+
+    pub fn default_button(mut self, id: &'static str) -> Dialog {
+        self.default_button = Some(id);
+        self
+    }
+
+    /**
+     * 화면 크기에 맞춰 대화상자를 가운데로 다시 배치한다.
+     */
+    pub fn resize(&mut self, width: u16, height: u16) {
+        self.x = width.saturating_sub(self.w) / 2;
+        self.y = height.saturating_sub(self.h) / 2;
+    }
+
+    pub fn value(&self, id: &str) -> WidgetValue {
+        self.entries
+            .iter()
+            .find(|entry| entry.id == id)
+            .map(|entry| entry.widget.value())
+            .unwrap_or(WidgetValue::None)
+    }
+
+    pub fn draw(&self, theme: &Theme) {
+        set_color(theme.dialog.fg, theme.dialog.bg);
+        Rect::draw(&std::io::stdout(), self.x, self.y, self.w, self.h);
+
+        let title = format!(" {} ", self.title);
+        let title_x = self.x + self.w.saturating_sub(title.width_cjk() as u16) / 2;
+        move_to(title_x, self.y);
+        print!("{}", title);
+
+        for (i, entry) in self.entries.iter().enumerate() {
+            set_color(theme.dialog.fg, theme.dialog.bg);
+            entry
+                .widget
+                .draw(self.x + entry.x, self.y + entry.y, i == self.focus, theme);
+        }
+
+        if let Err(e) = queue!(std::io::stdout(), ResetColor) {
+            error!("Dialog::draw: {}", e);
+        }
+    }
+
+    /**
+     * 포커스를 가진 위젯이 원하는 터미널 커서 위치 (화면 좌표)
+     */
+    pub fn cursor_position(&self) -> Option<(u16, u16)> {
+        self.entries.get(self.focus).map(|entry| {
+            let (x, y) = entry.widget.cursor();
+            (self.x + entry.x + x, self.y + entry.y + y)
+        })
+    }
+
+    pub fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> DialogEvent {
+        match (modifier, code) {
+            (_, KeyCode::Esc) => return self.close(None),
+            (KeyModifiers::NONE, KeyCode::Tab) => {
+                self.move_focus(1);
+                return DialogEvent::None;
+            }
+            (_, KeyCode::BackTab) => {
+                self.move_focus(-1);
+                return DialogEvent::None;
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) if !self.focused_is_button() => {
+                return match self.default_button {
+                    Some(id) => self.close(Some(id)),
+                    None => DialogEvent::None,
+                };
+            }
+            _ => {}
+        }
+
+        let focus = self.focus;
+        let event = match self.entries.get_mut(focus) {
+            Some(entry) => entry.widget.handle_keyinput(modifier, code),
+            None => return DialogEvent::None,
+        };
+        if event != WidgetEvent::Ignored {
+            return self.widget_event(focus, event);
+        }
+
+        // 위젯이 쓰지 않은 키: 버튼 사이의 방향키 이동, 버튼 단축 글자
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Left) | (KeyModifiers::NONE, KeyCode::Up)
+                if self.focused_is_button() =>
+            {
+                self.move_focus(-1);
+                DialogEvent::None
+            }
+            (KeyModifiers::NONE, KeyCode::Right) | (KeyModifiers::NONE, KeyCode::Down)
+                if self.focused_is_button() =>
+            {
+                self.move_focus(1);
+                DialogEvent::None
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c))
+            | (KeyModifiers::SHIFT, KeyCode::Char(c))
+            | (KeyModifiers::ALT, KeyCode::Char(c)) => {
+                let c = c.to_ascii_lowercase();
+                match self
+                    .entries
+                    .iter()
+                    .find(|entry| entry.widget.mnemonic() == Some(c))
+                {
+                    Some(entry) => self.close(Some(entry.id)),
+                    None => DialogEvent::None,
+                }
+            }
+            _ => DialogEvent::None,
+        }
+    }
+
+    /**
+     * 위젯을 클릭하면 포커스를 옮기고 위젯에 넘긴다. 대화상자 바깥 클릭은 무시한다.
+     */
+    pub fn handle_mouse(&mut self, kind: MouseEventKind, x: u16, y: u16) -> DialogEvent {
+        let idx = match self.entry_at(x, y) {
+            Some(idx) => idx,
+            None => return DialogEvent::None,
+        };
+
+        let (wx, wy) = (self.x + self.entries[idx].x, self.y + self.entries[idx].y);
+        let widget = &mut self.entries[idx].widget;
+        let event = match kind {
+            MouseEventKind::Down(MouseButton::Left) => {
+                if widget.focusable() {
+                    self.focus = idx;
+                }
+                self.entries[idx].widget.handle_click(x - wx, y - wy)
+            }
+            MouseEventKind::ScrollUp => widget.handle_scroll(-1),
+            MouseEventKind::ScrollDown => widget.handle_scroll(1),
+            _ => WidgetEvent::Ignored,
+        };
+        self.widget_event(idx, event)
+    }
+
+    fn widget_event(&mut self, idx: usize, event: WidgetEvent) -> DialogEvent {
+        match event {
+            WidgetEvent::Pressed => self.close(Some(self.entries[idx].id)),
+            WidgetEvent::Changed => DialogEvent::Changed(self.entries[idx].id),
+            WidgetEvent::Handled | WidgetEvent::Ignored => DialogEvent::None,
+        }
+    }
+
+    fn entry_at(&self, x: u16, y: u16) -> Option<usize> {
+        self.entries.iter().position(|entry| {
+            let (w, h) = entry.widget.size();
+            let (ex, ey) = (self.x + entry.x, self.y + entry.y);
+            x >= ex && x < ex + w && y >= ey && y < ey + h
+        })
+    }
+
+    fn focused_is_button(&self) -> bool {
+        self.entries
+            .get(self.focus)
+            .is_some_and(|entry| entry.widget.is_button())
+    }
+
+    /**
+     * 포커스 가능한 다음(이전) 위젯으로 옮긴다. 끝에 닿으면 반대쪽 끝으로 돈다.
+     */
+    fn move_focus(&mut self, step: isize) {
+        let len = self.entries.len() as isize;
+        let mut idx = self.focus as isize;
+        for _ in 0..len {
+            idx = (idx + step).rem_euclid(len);
+            if self.entries[idx as usize].widget.focusable() {
+                self.focus = idx as usize;
+                return;
+            }
+        }
+    }
+
+    fn close(&self, button: Option<&'static str>) -> DialogEvent {
+        DialogEvent::Closed(DialogResult {
+            button,
+            values: self
+                .entries
+                .iter()
+                .map(|entry| (entry.id, entry.widget.value()))
+                .collect(),
+        })
+    }
+}
+
+/**
+ * 위젯이 그릴 위치로 커서를 옮긴다.
+ */
+fn move_to(x: u16, y: u16) {
+    if let Err(e) = queue!(std::io::stdout(), cursor::MoveTo(x, y)) {
+        error!("dialog move_to: {}", e);
+    }
+}
+
+/**
+ * 글자를 화면 너비에 맞춰 자르거나 공백으로 채운다.
+ */
+fn fit(s: &str, width: usize) -> String {
+    let mut result = String::new();
+    let mut used = 0;
+    for c in s.chars() {
+        let w = UnicodeWidthChar::width_cjk(c).unwrap_or(0);
+        if used + w > width {
+            break;
+        }
+        result.push(c);
+        used += w;
+    }
+    result.push_str(&" ".repeat(width - used));
+    result
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> Dialog {
+        let mut dialog = Dialog::new("test", "Test", 40, 10)
+            .add("label", 2, 1, Label::new("Name:"))
+            .add("name", 8, 1, TextInput::new(20, "abc"))
+            .add("wrap", 2, 3, CheckBox::new("Wrap", false))
+            .add("ok", 4, 8, Button::new("OK"))
+            .add("cancel", 14, 8, Button::new("Cancel"))
+            .default_button("ok");
+        dialog.resize(80, 24);
+        dialog
+    }
+
+    #[test]
+    fn test_focus() {
+        let mut dialog = sample();
+        // Label 은 포커스를 받지 않는다.
+        assert_eq!(dialog.focus, 1);
+
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Tab);
+        assert_eq!(dialog.focus, 2);
+        dialog.handle_keyinput(KeyModifiers::SHIFT, KeyCode::BackTab);
+        dialog.handle_keyinput(KeyModifiers::SHIFT, KeyCode::BackTab);
+        assert_eq!(dialog.focus, 4);
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Tab);
+        assert_eq!(dialog.focus, 1);
+    }
+
+    #[test]
+    fn test_result() {
+        let mut dialog = sample();
+        assert_eq!(
+            dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('d')),
+            DialogEvent::Changed("name")
+        );
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Tab);
+        dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Char(' '));
+
+        match dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter) {
+            DialogEvent::Closed(result) => {
+                assert_eq!(result.button, Some("ok"));
+                assert_eq!(result.text("name"), Some("abcd"));
+                assert_eq!(result.checked("wrap"), Some(true));
+                assert_eq!(result.selected("wrap"), None);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+
+        match dialog.handle_keyinput(KeyModifiers::NONE, KeyCode::Esc) {
+            DialogEvent::Closed(result) => assert_eq!(result.button, None),
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+
+    #[test]
+    fn test_mouse() {
+        let mut dialog = sample();
+        let (x, y) = (dialog.x, dialog.y);
+        let click = MouseEventKind::Down(MouseButton::Left);
+
+        assert_eq!(
+            dialog.handle_mouse(click, x + 3, y + 3),
+            DialogEvent::Changed("wrap")
+        );
+        assert_eq!(dialog.focus, 2);
+        assert_eq!(dialog.handle_mouse(click, 0, 0), DialogEvent::None);
+        match dialog.handle_mouse(click, x + 15, y + 8) {
+            DialogEvent::Closed(result) => assert_eq!(result.button, Some("cancel")),
+            event => panic!("unexpected {:?}", event),
+        }
+    }
+}
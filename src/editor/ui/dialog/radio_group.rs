@@ -0,0 +1,95 @@
+use super::{move_to, Widget, WidgetEvent, WidgetValue};
+use crate::editor::ui::theme::Theme;
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * 세로로 늘어선 `(•) 항목` 중 하나를 고르는 위젯. 방향키나 클릭으로 고른다.
+ */
+pub struct RadioGroup {
+    options: Vec<String>,
+    selected: usize,
+}
+
+impl RadioGroup {
+    pub fn new(options: &[&str], selected: usize) -> RadioGroup {
+        RadioGroup {
+            options: options.iter().map(|s| String::from(*s)).collect(),
+            selected: selected.min(options.len().saturating_sub(1)),
+        }
+    }
+
+    fn select(&mut self, idx: usize) -> WidgetEvent {
+        if idx >= self.options.len() || idx == self.selected {
+            return WidgetEvent::Handled;
+        }
+
+        self.selected = idx;
+        WidgetEvent::Changed
+    }
+}
+
+impl Widget for RadioGroup {
+    fn draw(&self, x: u16, y: u16, _focused: bool, _theme: &Theme) {
+        for (i, option) in self.options.iter().enumerate() {
+            move_to(x, y + i as u16);
+            let mark = if i == self.selected { '•' } else { ' ' };
+            print!("({}) {}", mark, option);
+        }
+    }
+
+    fn size(&self) -> (u16, u16) {
+        let width = self
+            .options
+            .iter()
+            .map(|s| s.width_cjk())
+            .max()
+            .unwrap_or(0);
+        (width as u16 + 4, self.options.len() as u16)
+    }
+
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> WidgetEvent {
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Up) | (KeyModifiers::NONE, KeyCode::Left) => {
+                self.select(self.selected.saturating_sub(1))
+            }
+            (KeyModifiers::NONE, KeyCode::Down) | (KeyModifiers::NONE, KeyCode::Right) => {
+                self.select(self.selected + 1)
+            }
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    fn handle_click(&mut self, _x: u16, y: u16) -> WidgetEvent {
+        self.select(y as usize)
+    }
+
+    fn value(&self) -> WidgetValue {
+        WidgetValue::Selected(Some(self.selected))
+    }
+
+    fn cursor(&self) -> (u16, u16) {
+        (1, self.selected as u16)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_select() {
+        let mut radio = RadioGroup::new(&["a", "b", "c"], 5);
+        assert_eq!(radio.value(), WidgetValue::Selected(Some(2)));
+        assert_eq!(
+            radio.handle_keyinput(KeyModifiers::NONE, KeyCode::Down),
+            WidgetEvent::Handled
+        );
+        assert_eq!(
+            radio.handle_keyinput(KeyModifiers::NONE, KeyCode::Up),
+            WidgetEvent::Changed
+        );
+        assert_eq!(radio.handle_click(3, 0), WidgetEvent::Changed);
+        assert_eq!(radio.value(), WidgetValue::Selected(Some(0)));
+    }
+}
@@ -0,0 +1,168 @@
+use super::{move_to, Widget, WidgetEvent, WidgetValue};
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::event::{KeyCode, KeyModifiers};
+use unicode_width::UnicodeWidthChar;
+
+/**
+ * 한 줄짜리 입력 칸. 내용이 칸보다 길면 커서가 보이도록 가로로 스크롤한다.
+ */
+pub struct TextInput {
+    chars: Vec<char>,
+    /// 커서가 있는 글자 위치
+    caret: usize,
+    /// 칸 맨 왼쪽에 보이는 글자 위치
+    offset: usize,
+    w: u16,
+}
+
+impl TextInput {
+    pub fn new(w: u16, text: &str) -> TextInput {
+        let mut input = TextInput {
+            chars: text.chars().collect(),
+            caret: 0,
+            offset: 0,
+            w: w.max(2),
+        };
+        input.move_caret(input.chars.len());
+        input
+    }
+
+    fn width_between(&self, from: usize, to: usize) -> usize {
+        self.chars[from..to]
+            .iter()
+            .map(|c| c.width_cjk().unwrap_or(0))
+            .sum()
+    }
+
+    /**
+     * 커서를 옮기고 커서가 칸 안에 보이도록 offset 을 맞춘다.
+     */
+    fn move_caret(&mut self, caret: usize) {
+        self.caret = caret.min(self.chars.len());
+        if self.caret < self.offset {
+            self.offset = self.caret;
+        }
+        // 커서 자리 한 칸은 비워둔다.
+        while self.width_between(self.offset, self.caret) >= self.w as usize {
+            self.offset += 1;
+        }
+    }
+}
+
+impl Widget for TextInput {
+    fn draw(&self, x: u16, y: u16, _focused: bool, theme: &Theme) {
+        set_color(theme.text.fg, theme.text.bg);
+        move_to(x, y);
+
+        let mut used = 0;
+        for c in &self.chars[self.offset..] {
+            let width = c.width_cjk().unwrap_or(0);
+            if used + width > self.w as usize {
+                break;
+            }
+            print!("{}", c);
+            used += width;
+        }
+        print!("{:1$}", "", self.w as usize - used);
+    }
+
+    fn size(&self) -> (u16, u16) {
+        (self.w, 1)
+    }
+
+    fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> WidgetEvent {
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                self.chars.insert(self.caret, c);
+                self.move_caret(self.caret + 1);
+                WidgetEvent::Changed
+            }
+            (KeyModifiers::NONE, KeyCode::Backspace) if self.caret > 0 => {
+                self.chars.remove(self.caret - 1);
+                self.move_caret(self.caret - 1);
+                WidgetEvent::Changed
+            }
+            (KeyModifiers::NONE, KeyCode::Delete) if self.caret < self.chars.len() => {
+                self.chars.remove(self.caret);
+                WidgetEvent::Changed
+            }
+            (KeyModifiers::NONE, KeyCode::Left) => {
+                self.move_caret(self.caret.saturating_sub(1));
+                WidgetEvent::Handled
+            }
+            (KeyModifiers::NONE, KeyCode::Right) => {
+                self.move_caret(self.caret + 1);
+                WidgetEvent::Handled
+            }
+            (KeyModifiers::NONE, KeyCode::Home) => {
+                self.move_caret(0);
+                WidgetEvent::Handled
+            }
+            (KeyModifiers::NONE, KeyCode::End) => {
+                self.move_caret(self.chars.len());
+                WidgetEvent::Handled
+            }
+            _ => WidgetEvent::Ignored,
+        }
+    }
+
+    /**
+     * 클릭한 글자 앞으로 커서를 옮긴다.
+     */
+    fn handle_click(&mut self, x: u16, _y: u16) -> WidgetEvent {
+        let mut used = 0;
+        let mut caret = self.offset;
+        while caret < self.chars.len() {
+            used += self.chars[caret].width_cjk().unwrap_or(0);
+            if used > x as usize {
+                break;
+            }
+            caret += 1;
+        }
+        self.move_caret(caret);
+        WidgetEvent::Handled
+    }
+
+    fn value(&self) -> WidgetValue {
+        WidgetValue::Text(self.chars.iter().collect())
+    }
+
+    fn cursor(&self) -> (u16, u16) {
+        (self.width_between(self.offset, self.caret) as u16, 0)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let mut input = TextInput::new(4, "abcdef");
+        assert_eq!((input.caret, input.offset), (6, 3));
+        assert_eq!(input.cursor(), (3, 0));
+
+        input.handle_keyinput(KeyModifiers::NONE, KeyCode::Home);
+        assert_eq!((input.caret, input.offset), (0, 0));
+        input.handle_keyinput(KeyModifiers::NONE, KeyCode::Delete);
+        input.handle_keyinput(KeyModifiers::SHIFT, KeyCode::Char('X'));
+        assert_eq!(
+            input.handle_keyinput(KeyModifiers::NONE, KeyCode::Backspace),
+            WidgetEvent::Changed
+        );
+        assert_eq!(
+            input.handle_keyinput(KeyModifiers::NONE, KeyCode::Backspace),
+            WidgetEvent::Ignored
+        );
+        assert_eq!(input.value(), WidgetValue::Text(String::from("bcdef")));
+
+        // 한글은 두 칸을 차지한다.
+        let mut input = TextInput::new(5, "한글");
+        assert_eq!(input.cursor(), (4, 0));
+        input.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('a'));
+        assert_eq!((input.offset, input.cursor()), (1, (3, 0)));
+        input.handle_click(0, 0);
+        assert_eq!(input.caret, 1);
+    }
+}
@@ -0,0 +1,115 @@
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::style::ResetColor;
+use crossterm::{cursor, queue};
+use log::error;
+use std::io::Stdout;
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GutterMode {
+    Off,
+    Absolute,
+    /// 현재 줄은 절대 번호, 나머지 줄은 현재 줄로부터의 거리를 표시
+    Relative,
+}
+
+/**
+ * 본문 왼쪽에 줄 번호를 출력하는 영역
+ */
+pub struct Gutter {
+    pub mode: GutterMode,
+}
+
+impl Gutter {
+    pub fn new() -> Gutter {
+        Gutter {
+            mode: GutterMode::Off,
+        }
+    }
+
+    /**
+        줄 번호 영역의 너비. 전체 줄 수의 자릿수 + 본문과의 간격 1칸.
+
+        # Arguments
+        * `total_lines` - 전체 줄 수
+    */
+    pub fn width(&self, total_lines: usize) -> u16 {
+        match self.mode {
+            GutterMode::Off => 0,
+            _ => total_lines.max(1).to_string().len() as u16 + 1,
+        }
+    }
+
+    /**
+        한 줄의 번호를 오른쪽 정렬로 그린다.
+
+        # Arguments
+        * `(x, y)` - 화면 위치. x 는 창의 왼쪽 끝
+        * `line` - 그릴 줄의 인덱스 (0부터)
+        * `current` - 커서가 있는 줄의 인덱스 (0부터)
+        * `total_lines` - 전체 줄 수
+        * `theme` - 색 설정
+    */
+    pub fn draw(
+        &self,
+        mut screen: &Stdout,
+        (x, y): (u16, u16),
+        line: usize,
+        current: usize,
+        total_lines: usize,
+        theme: &Theme,
+    ) {
+        let width = self.width(total_lines) as usize;
+        if width == 0 {
+            return;
+        }
+
+        if let Err(e) = queue!(screen, cursor::MoveTo(x, y)) {
+            error!("Gutter::draw: {}", e);
+            return;
+        }
+
+        set_color(theme.gutter.fg, theme.gutter.bg);
+        print!("{:>w$} ", self.label(line, current), w = width - 1);
+
+        if let Err(e) = queue!(screen, ResetColor) {
+            error!("Gutter::draw: {}", e);
+        }
+    }
+
+    fn label(&self, line: usize, current: usize) -> usize {
+        match self.mode {
+            GutterMode::Relative if line != current => line.abs_diff(current),
+            _ => line + 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_width() {
+        let mut g = Gutter::new();
+        assert_eq!(g.width(5000), 0);
+
+        g.mode = GutterMode::Absolute;
+        assert_eq!(g.width(0), 2);
+        assert_eq!(g.width(9), 2);
+        assert_eq!(g.width(10), 3);
+        assert_eq!(g.width(12345), 6);
+    }
+
+    #[test]
+    fn test_label() {
+        let mut g = Gutter::new();
+        g.mode = GutterMode::Absolute;
+        assert_eq!(g.label(4, 10), 5);
+
+        g.mode = GutterMode::Relative;
+        assert_eq!(g.label(4, 10), 6);
+        assert_eq!(g.label(10, 10), 11);
+        assert_eq!(g.label(13, 10), 3);
+    }
+}
@@ -0,0 +1,431 @@
+use crate::editor::ui::rect::Rect;
+use crate::editor::ui::split::Area;
+use crate::editor::ui::theme::{ColorPair, Theme};
+use crate::editor::util::set_color;
+use crossterm::style::ResetColor;
+use crossterm::{cursor, queue};
+use log::error;
+use std::io::Stdout;
+
+/// 오프셋 다음의 구분선 위치
+const OFFSET_SEPARATOR_X: usize = 9;
+/// 첫 번째 바이트의 16진수가 시작하는 위치
+const HEX_X: usize = 11;
+
+/**
+ * 커서가 있는 영역. 다른 영역에서는 같은 바이트를 강조해서 보여준다.
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Pane {
+    Hex,
+    Text,
+}
+
+/**
+파일 내용을 바이트 단위로 보여주고 고치는 화면. (View > Hex)
+줄마다 오프셋, 16진수, 글자 영역이 있고 Tab 으로 16진수/글자 영역을 오간다.
+바이트를 덮어쓰기만 하고, 끝에서 입력하면 바이트를 덧붙인다.
+*/
+pub struct HexView {
+    bytes: Vec<u8>,
+    /// 커서가 있는 바이트. 끝(bytes.len())에도 놓을 수 있다.
+    cursor: usize,
+    /// 16진수 영역에서 커서가 아래 4비트에 있는지 여부
+    low_nibble: bool,
+    pane: Pane,
+    /// 커서가 있는 창의 맨 위에 보이는 줄. 다른 창의 위치는 그 창(Pane)에 있다.
+    top: usize,
+    /// 커서가 있는 창의 한 줄 바이트 수. 창 너비에 따라 16 또는 8
+    cols: usize,
+    height: usize,
+    modified: bool,
+}
+
+impl HexView {
+    pub fn new(bytes: Vec<u8>) -> HexView {
+        HexView {
+            bytes,
+            cursor: 0,
+            low_nibble: false,
+            pane: Pane::Hex,
+            top: 0,
+            cols: 16,
+            height: 1,
+            modified: false,
+        }
+    }
+
+    pub fn bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+
+    pub fn modified(&self) -> bool {
+        self.modified
+    }
+
+    pub fn offset(&self) -> usize {
+        self.cursor
+    }
+
+    pub fn top(&self) -> usize {
+        self.top
+    }
+
+    /**
+     * 커서가 있는 창을 바꿀 때 그 창의 스크롤 위치를 넣는다. 다음 layout 에서 커서가 보이도록 맞춘다.
+     */
+    pub fn set_top(&mut self, top: usize) {
+        self.top = top;
+    }
+
+    /**
+     * 창 크기에 맞춰 한 줄의 바이트 수를 정하고 커서가 보이도록 스크롤한다.
+     */
+    pub fn layout(&mut self, width: usize, height: usize) {
+        self.cols = columns(width);
+        self.height = height.max(1);
+
+        let row = self.cursor / self.cols;
+        if row < self.top {
+            self.top = row;
+        } else if row >= self.top + self.height {
+            self.top = row + 1 - self.height;
+        }
+    }
+
+    /**
+    창 하나에 그린다. 같은 문서를 여러 창에서 보면 창마다 스크롤 위치와 한 줄의 바이트 수가 다르다.
+    창보다 긴 줄은 LineBuffer::draw 처럼 `width` 에서 한 칸을 뺀 곳에서 자른다.
+
+    # Arguments
+    * `screen` - 콘솔 화면 객체
+    * `area` - 그릴 창의 영역
+    * `top` - 창의 맨 위에 보이는 줄
+    * `width` - 배경색을 채울 너비. 마지막 한 칸은 비워 둔다.
+    * `theme` - 색 설정. 오프셋은 gutter, 다른 영역의 커서 위치는 selection 색
+    */
+    pub fn draw(&self, mut screen: &Stdout, area: &Area, top: usize, width: usize, theme: &Theme) {
+        let cols = columns(area.w as usize);
+        let max = width.saturating_sub(1);
+        for r in 0..area.h as usize {
+            if let Err(e) = queue!(screen, cursor::MoveTo(area.x, area.y + r as u16)) {
+                error!("HexView::draw: {}", e);
+                return;
+            }
+
+            let mut row = Row { x: 0, max };
+            let start = (top + r) * cols;
+            if start > self.bytes.len() || (start == self.bytes.len() && start > 0) {
+                set_color(theme.text.fg, theme.text.bg);
+                row.fill();
+                continue;
+            }
+
+            set_color(theme.gutter.fg, theme.gutter.bg);
+            row.print(&format!("{:08X}", start));
+            set_color(theme.text.fg, theme.text.bg);
+
+            for i in 0..cols {
+                row.pad_to(hex_x(cols, i));
+                match self.bytes.get(start + i) {
+                    Some(byte) => {
+                        self.set_cell_color(start + i, Pane::Hex, theme);
+                        row.print(&format!("{:02X}", byte));
+                        set_color(theme.text.fg, theme.text.bg);
+                    }
+                    None => row.print("  "),
+                }
+            }
+
+            row.pad_to(text_x(cols));
+            for i in 0..cols {
+                match self.bytes.get(start + i) {
+                    Some(&byte) => {
+                        self.set_cell_color(start + i, Pane::Text, theme);
+                        row.print(&printable(byte).to_string());
+                        set_color(theme.text.fg, theme.text.bg);
+                    }
+                    None => row.print(" "),
+                }
+            }
+            // 배경색이 줄 끝까지 보이도록 공백으로 채운다.
+            row.fill();
+        }
+
+        set_color(theme.text.fg, theme.text.bg);
+        for x in [OFFSET_SEPARATOR_X, text_separator_x(cols)] {
+            if x < max {
+                Rect::draw_vertical_line(screen, area.x + x as u16, area.y, area.h);
+            }
+        }
+
+        if let Err(e) = queue!(screen, ResetColor) {
+            error!("HexView::draw: {}", e);
+        }
+    }
+
+    /**
+     * 커서가 없는 영역에서 커서 위치의 바이트를 강조한다.
+     */
+    fn set_cell_color(&self, index: usize, pane: Pane, theme: &Theme) {
+        let colors: ColorPair = if index == self.cursor && pane != self.pane {
+            theme.selection
+        } else {
+            theme.text
+        };
+        set_color(colors.fg, colors.bg);
+    }
+
+    /**
+     * 화면의 커서 위치. `x`, `y` 는 창의 왼쪽 위 위치
+     */
+    pub fn cursor_position(&self, x: u16, y: u16) -> (u16, u16) {
+        let row = self.cursor / self.cols - self.top;
+        let i = self.cursor % self.cols;
+        let column = match self.pane {
+            Pane::Hex => hex_x(self.cols, i) + self.low_nibble as usize,
+            Pane::Text => text_x(self.cols) + i,
+        };
+        (x + column as u16, y + row as u16)
+    }
+
+    pub fn switch_pane(&mut self) {
+        self.pane = match self.pane {
+            Pane::Hex => Pane::Text,
+            Pane::Text => Pane::Hex,
+        };
+        self.low_nibble = false;
+    }
+
+    fn move_to(&mut self, cursor: usize) {
+        self.cursor = cursor.min(self.bytes.len());
+        self.low_nibble = false;
+    }
+
+    pub fn move_left(&mut self) {
+        if self.low_nibble {
+            self.low_nibble = false;
+        } else {
+            self.move_to(self.cursor.saturating_sub(1));
+        }
+    }
+
+    pub fn move_right(&mut self) {
+        self.move_to(self.cursor + 1);
+    }
+
+    pub fn move_up(&mut self) {
+        if self.cursor >= self.cols {
+            self.move_to(self.cursor - self.cols);
+        }
+    }
+
+    pub fn move_down(&mut self) {
+        if self.cursor / self.cols < self.bytes.len() / self.cols {
+            self.move_to(self.cursor + self.cols);
+        }
+    }
+
+    /**
+    커서 위치의 바이트를 고친다. 16진수 영역에서는 16진수 숫자를 4비트씩,
+    글자 영역에서는 ASCII 글자를 한 바이트로 입력한다.
+
+    # Return
+    * 입력한 글자를 받아들였는지 여부
+    */
+    pub fn input_char(&mut self, ch: char) -> bool {
+        let value = match self.pane {
+            Pane::Hex => match ch.to_digit(16) {
+                Some(digit) => digit as u8,
+                None => return false,
+            },
+            Pane::Text if (' '..='~').contains(&ch) => ch as u8,
+            Pane::Text => return false,
+        };
+
+        if self.cursor == self.bytes.len() {
+            self.bytes.push(0);
+        }
+        let byte = &mut self.bytes[self.cursor];
+        match self.pane {
+            Pane::Hex if !self.low_nibble => {
+                *byte = (value << 4) | (*byte & 0x0f);
+                self.low_nibble = true;
+            }
+            Pane::Hex => {
+                *byte = (*byte & 0xf0) | value;
+                self.move_to(self.cursor + 1);
+            }
+            Pane::Text => {
+                *byte = value;
+                self.move_to(self.cursor + 1);
+            }
+        }
+        self.modified = true;
+        true
+    }
+
+    /**
+     * 마우스로 누른 바이트로 커서를 옮긴다. `x`, `row` 는 창 왼쪽 위 기준
+     */
+    pub fn click(&mut self, x: usize, row: usize) {
+        let start = (self.top + row) * self.cols;
+        for i in 0..self.cols {
+            let hex_x = hex_x(self.cols, i);
+            if (hex_x..hex_x + 2).contains(&x) {
+                self.pane = Pane::Hex;
+                self.move_to(start + i);
+                self.low_nibble = x == hex_x + 1 && self.cursor == start + i;
+                return;
+            }
+            if x == text_x(self.cols) + i {
+                self.pane = Pane::Text;
+                self.move_to(start + i);
+                return;
+            }
+        }
+    }
+
+    /**
+     * 마우스 휠. 화면을 굴리고 커서가 화면 밖으로 나가면 안으로 옮긴다.
+     */
+    pub fn scroll(&mut self, delta: i32) {
+        let last_row = self.bytes.len() / self.cols;
+        let top = (self.top as i64 + delta as i64).clamp(0, last_row as i64);
+        self.top = top as usize;
+
+        let row = self.cursor / self.cols;
+        let col = self.cursor % self.cols;
+        if row < self.top {
+            self.move_to(self.top * self.cols + col);
+        } else if row >= self.top + self.height {
+            self.move_to((self.top + self.height - 1) * self.cols + col);
+        }
+    }
+}
+
+/**
+ * 한 줄의 너비. 오프셋, 16진수, 글자 영역과 구분선
+ */
+fn line_width(cols: usize) -> usize {
+    HEX_X + cols * 3 + 1 + 2 + cols
+}
+
+/**
+ * 창 너비에 맞는 한 줄의 바이트 수
+ */
+fn columns(width: usize) -> usize {
+    if width > line_width(16) {
+        16
+    } else {
+        8
+    }
+}
+
+fn hex_x(cols: usize, i: usize) -> usize {
+    // 가운데에 한 칸을 더 띄운다.
+    HEX_X + i * 3 + if i >= cols / 2 { 1 } else { 0 }
+}
+
+fn text_separator_x(cols: usize) -> usize {
+    HEX_X + cols * 3 + 1
+}
+
+fn text_x(cols: usize) -> usize {
+    text_separator_x(cols) + 2
+}
+
+/**
+ * 그리는 중인 한 줄. `max` 칸을 넘는 부분은 그리지 않는다. (ASCII 만 그린다)
+ */
+struct Row {
+    x: usize,
+    max: usize,
+}
+
+impl Row {
+    fn print(&mut self, s: &str) {
+        let visible = s.len().min(self.max.saturating_sub(self.x));
+        print!("{}", &s[..visible]);
+        self.x += s.len();
+    }
+
+    fn pad_to(&mut self, x: usize) {
+        self.print(&" ".repeat(x.saturating_sub(self.x)));
+    }
+
+    fn fill(&mut self) {
+        self.pad_to(self.max);
+    }
+}
+
+fn printable(byte: u8) -> char {
+    if (0x20..0x7f).contains(&byte) {
+        byte as char
+    } else {
+        '.'
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_edit() {
+        let mut hex = HexView::new(vec![0x00, 0x11]);
+        hex.layout(80, 10);
+
+        assert!(hex.input_char('a'));
+        assert_eq!(hex.cursor_position(0, 1), (12, 1));
+        assert!(hex.input_char('B'));
+        assert!(!hex.input_char('g'));
+        assert_eq!(hex.bytes(), &[0xab, 0x11]);
+        assert_eq!(hex.offset(), 1);
+
+        // 글자 영역에서 입력하고, 끝에서 입력하면 덧붙인다.
+        hex.switch_pane();
+        assert!(hex.input_char('z'));
+        assert!(hex.input_char('!'));
+        assert!(!hex.input_char('한'));
+        assert_eq!(hex.bytes(), &[0xab, b'z', b'!']);
+        assert!(hex.modified());
+
+        hex.move_right();
+        assert_eq!(hex.offset(), 3);
+        hex.move_up();
+        assert_eq!(hex.offset(), 3);
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut hex = HexView::new((0..=255).collect());
+        hex.layout(80, 4);
+        assert_eq!(line_width(16), 78);
+        assert_eq!(hex.cols, 16);
+        assert_eq!(text_separator_x(hex.cols), 60);
+
+        // 8 번째 바이트 앞에는 한 칸을 더 띄운다.
+        hex.move_to(0x18);
+        assert_eq!(hex.cursor_position(0, 1), (11 + 8 * 3 + 1, 2));
+
+        hex.move_to(0x80);
+        hex.layout(80, 4);
+        assert_eq!(hex.top, 5);
+        hex.switch_pane();
+        assert_eq!(hex.cursor_position(0, 1), (62, 4));
+
+        hex.layout(60, 4);
+        assert_eq!(hex.cols, 8);
+        assert_eq!(hex.cursor_position(0, 1), (11 + 8 * 3 + 1 + 2, 4));
+
+        hex.click(11 + 3 + 1, 0);
+        assert_eq!(hex.pane, Pane::Hex);
+        assert_eq!(hex.offset(), 13 * 8 + 1);
+        assert!(hex.low_nibble);
+    }
+}
@@ -1,52 +1,406 @@
-use super::menu_item::MenuItem;
+use super::menu_item::{ItemState, MenuItem};
+use super::{draw_label, mnemonic_char, parse_mnemonic};
+use crate::check_result;
 use crate::consts::ui;
 use crate::editor::ui::rect::Rect;
+use crate::editor::ui::theme::{ColorPair, Theme};
 use crate::editor::util::set_color;
+use crossterm::event::{KeyCode, KeyModifiers};
 use crossterm::{cursor, queue};
+use unicode_width::UnicodeWidthStr;
+
+/// 항목 이름과 단축키 외에 필요한 너비: 테두리 2, 체크 표시 칸 3, 이름과 단축키 사이 2, 오른쪽 여백 1
+const ITEM_EXTRA_WIDTH: usize = 8;
+
+/**
+ * 메뉴 그룹이 키 입력을 처리한 결과
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum GroupAction {
+    Cmd(ui::MenuCmd),
+    /// 이 그룹을 닫아야 함 (Left, Esc)
+    Close,
+    /// 그룹에서 처리하지 않은 키. 메뉴 막대가 처리한다.
+    Unhandled,
+}
 
 #[derive(Debug)]
 pub struct MenuGroup {
     pub name: String,
-    index: u16,
+    /// 이름에서 밑줄로 표시되는 단축 글자의 위치 (Alt 와 같이 눌러서 연다)
+    pub mnemonic: Option<usize>,
+    /// 드롭다운 상자의 위치. 그릴 때마다 layout 으로 정한다.
+    x: u16,
+    y: u16,
     items: Vec<MenuItem>,
     selected: usize,
+    /// 선택된 항목의 하위 메뉴가 열려 있는지 여부
+    submenu_open: bool,
 }
 
 impl MenuGroup {
-    pub fn new(group_name: &str, idx: u16) -> MenuGroup {
+    /**
+    # Arguments
+    * `group_name` - 그룹 이름. '&' 바로 뒤의 글자가 단축 글자가 된다. (ex: "&File")
+    */
+    pub fn new(group_name: &str) -> MenuGroup {
+        let (name, mnemonic) = parse_mnemonic(group_name);
         MenuGroup {
-            name: String::from(group_name),
-            index: idx,
+            name,
+            mnemonic,
+            x: 0,
+            y: 1,
             items: Vec::new(),
             selected: 0,
+            submenu_open: false,
         }
     }
 
     pub fn get_menu_cmd(&self) -> ui::MenuCmd {
-        self.items[self.selected].cmd
+        match self.items.get(self.selected) {
+            Some(item) if item.selectable() => item.cmd,
+            _ => ui::MenuCmd::None,
+        }
+    }
+
+    /**
+    키 입력 처리. 하위 메뉴가 열려 있으면 가장 안쪽 메뉴가 먼저 처리한다.
+    하위 메뉴가 Close 를 돌려주면 그 하위 메뉴만 닫는다.
+    */
+    pub fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> GroupAction {
+        if let Some(submenu) = self.open_submenu_mut() {
+            return match submenu.handle_keyinput(modifier, code) {
+                GroupAction::Close => {
+                    self.submenu_open = false;
+                    GroupAction::Cmd(ui::MenuCmd::Refresh)
+                }
+                action => action,
+            };
+        }
+
+        match (modifier, code) {
+            (KeyModifiers::NONE, KeyCode::Up) => {
+                self.move_up();
+                GroupAction::Cmd(ui::MenuCmd::Refresh)
+            }
+            (KeyModifiers::NONE, KeyCode::Down) => {
+                self.move_down();
+                GroupAction::Cmd(ui::MenuCmd::Refresh)
+            }
+            (KeyModifiers::NONE, KeyCode::Enter) => GroupAction::Cmd(self.activate(self.selected)),
+            (KeyModifiers::NONE, KeyCode::Right) if self.has_submenu(self.selected) => {
+                GroupAction::Cmd(self.activate(self.selected))
+            }
+            (KeyModifiers::NONE, KeyCode::Left) | (KeyModifiers::NONE, KeyCode::Esc) => {
+                GroupAction::Close
+            }
+            (KeyModifiers::NONE, KeyCode::Char(c)) | (KeyModifiers::SHIFT, KeyCode::Char(c)) => {
+                match self.find_mnemonic(c.to_ascii_lowercase()) {
+                    Some(idx) => GroupAction::Cmd(self.activate(idx)),
+                    None => GroupAction::Cmd(ui::MenuCmd::None),
+                }
+            }
+            _ => GroupAction::Unhandled,
+        }
+    }
+
+    /**
+    마우스 클릭 처리. 열려 있는 하위 메뉴부터 확인한다.
+
+    # Return
+    * 이 그룹이나 하위 메뉴 안을 클릭했으면 Some(명령), 바깥이면 None
+    */
+    pub fn handle_mouse(&mut self, x: u16, y: u16) -> Option<ui::MenuCmd> {
+        if let Some(submenu) = self.open_submenu_mut() {
+            if let Some(cmd) = submenu.handle_mouse(x, y) {
+                return Some(cmd);
+            }
+        }
+
+        self.item_at(x, y).map(|idx| self.activate(idx))
+    }
+
+    /**
+     * 위 방향키. 구분선과 비활성 항목은 건너뛴다.
+     */
+    pub fn move_up(&mut self) {
+        let len = self.items.len();
+        self.move_selection(|idx| (idx + len - 1) % len);
+    }
+
+    /**
+     * 아래 방향키. 구분선과 비활성 항목은 건너뛴다.
+     */
+    pub fn move_down(&mut self) {
+        let len = self.items.len();
+        self.move_selection(|idx| (idx + 1) % len);
+    }
+
+    fn move_selection(&mut self, step: impl Fn(usize) -> usize) {
+        self.submenu_open = false;
+
+        let mut idx = self.selected;
+        for _ in 0..self.items.len() {
+            idx = step(idx);
+            if self.items[idx].selectable() {
+                self.selected = idx;
+                return;
+            }
+        }
+    }
+
+    /**
+    에디터 상태에 따라 각 항목의 활성/체크 상태를 갱신한다. 하위 메뉴도 같이 갱신한다.
+    선택된 항목이 비활성이 되면 다음 항목으로 옮긴다.
+
+    # Arguments
+    * `state` - 명령별 항목 상태를 돌려주는 함수
+    */
+    pub fn update_state(&mut self, state: &dyn Fn(ui::MenuCmd) -> ItemState) {
+        for item in self.items.iter_mut().filter(|item| !item.separator) {
+            item.state = state(item.cmd);
+            if let Some(submenu) = &mut item.submenu {
+                submenu.update_state(state);
+            }
+        }
+
+        if !self.items.is_empty() && !self.items[self.selected].selectable() {
+            self.move_down();
+        }
+    }
+
+    /**
+     * 단축 글자가 ch 인 항목의 인덱스 (대소문자 무시)
+     */
+    pub fn find_mnemonic(&self, ch: char) -> Option<usize> {
+        self.items.iter().position(|item| {
+            item.selectable() && mnemonic_char(&item.name, item.mnemonic) == Some(ch)
+        })
+    }
+
+    /**
+     * 항목을 선택하고 그 항목의 명령을 돌려준다. (마우스 클릭, 단축 글자)
+     */
+    pub fn select(&mut self, idx: usize) -> ui::MenuCmd {
+        self.selected = idx;
+        self.get_menu_cmd()
+    }
+
+    /**
+    항목을 실행한다. 하위 메뉴가 있는 항목이면 하위 메뉴를 열고, 아니면 항목의 명령을 돌려준다.
+    구분선이나 비활성 항목이면 아무것도 하지 않는다.
+    */
+    fn activate(&mut self, idx: usize) -> ui::MenuCmd {
+        if !self.items[idx].selectable() {
+            return ui::MenuCmd::None;
+        }
+
+        self.submenu_open = false;
+        if let Some(submenu) = &mut self.items[idx].submenu {
+            submenu.reset();
+            self.selected = idx;
+            self.submenu_open = true;
+            return ui::MenuCmd::Refresh;
+        }
+
+        self.select(idx)
+    }
+
+    fn has_submenu(&self, idx: usize) -> bool {
+        matches!(self.items.get(idx), Some(item) if item.submenu.is_some())
+    }
+
+    fn open_submenu_mut(&mut self) -> Option<&mut MenuGroup> {
+        if !self.submenu_open {
+            return None;
+        }
+
+        match self.items.get_mut(self.selected) {
+            Some(item) => item.submenu.as_mut(),
+            None => None,
+        }
+    }
+
+    /**
+     * 열려 있는 하위 메뉴를 모두 닫고 첫 번째로 고를 수 있는 항목을 선택한다.
+     */
+    pub fn reset(&mut self) {
+        self.close_submenus();
+        self.selected = self
+            .items
+            .iter()
+            .position(|item| item.selectable())
+            .unwrap_or(0);
+    }
+
+    /**
+     * 열려 있는 하위 메뉴를 모두 닫는다.
+     */
+    pub fn close_submenus(&mut self) {
+        if let Some(submenu) = self.open_submenu_mut() {
+            submenu.close_submenus();
+        }
+        self.submenu_open = false;
+    }
+
+    /**
+     * 화면 위치 (x, y) 에 그려진 항목의 인덱스. 테두리나 바깥이면 None.
+     */
+    pub fn item_at(&self, x: u16, y: u16) -> Option<usize> {
+        let (left, top, w, _) = self.bounds();
+        if x <= left || x >= left + w - 1 || y <= top {
+            return None;
+        }
+
+        let idx = (y - top - 1) as usize;
+        if idx < self.items.len() {
+            Some(idx)
+        } else {
+            None
+        }
+    }
+
+    /**
+     * 드롭다운 상자의 (x, y, w, h)
+     */
+    fn bounds(&self) -> (u16, u16, u16, u16) {
+        (self.x, self.y, self.width(), self.items.len() as u16 + 2)
+    }
+
+    /**
+     * 드롭다운 상자 너비. 가장 긴 (항목 이름 + 단축키) 에 테두리, 체크 표시 칸, 여백을 더한다.
+     */
+    pub fn width(&self) -> u16 {
+        let widest = self
+            .items
+            .iter()
+            .map(|item| item.name.width_cjk() + item.right_label().width_cjk())
+            .max()
+            .unwrap_or(0);
+
+        (widest + ITEM_EXTRA_WIDTH) as u16
+    }
+
+    /**
+    드롭다운 상자의 위치를 정한다. 화면 오른쪽을 넘어가면 왼쪽으로 민다.
+
+    # Arguments
+    * `name_x` - 메뉴 막대에서 그룹 이름의 가로 위치
+    * `screen_width` - 화면 너비
+    */
+    pub fn layout(&mut self, name_x: u16, screen_width: u16) {
+        let x = name_x.saturating_sub(1);
+        self.x = std::cmp::min(x, screen_width.saturating_sub(self.width()));
+        self.y = 1;
+    }
+
+    /**
+    하위 메뉴의 위치를 정한다. 부모 상자 오른쪽, 첫 항목이 부모의 선택된 항목과 같은 줄에 오도록 하고
+    화면 오른쪽을 넘어가면 부모 상자 왼쪽에, 아래쪽을 넘어가면 위로 올려서 놓는다.
+
+    # Arguments
+    * `parent` - 부모 상자의 (x, y, w, h)
+    * `row` - 부모에서 선택된 항목의 화면 세로 위치
+    * `screen_width`, `screen_height` - 화면 크기
+    */
+    fn layout_submenu(
+        &mut self,
+        parent: (u16, u16, u16, u16),
+        row: u16,
+        screen_width: u16,
+        screen_height: u16,
+    ) {
+        let (parent_x, _, parent_w, _) = parent;
+        let (w, h) = (self.width(), self.items.len() as u16 + 2);
+
+        self.x = if parent_x + parent_w + w <= screen_width {
+            parent_x + parent_w
+        } else {
+            parent_x.saturating_sub(w)
+        };
+        self.y = std::cmp::min(row.saturating_sub(1), screen_height.saturating_sub(h));
     }
 
     pub fn add_item(&mut self, new_item: MenuItem) {
         self.items.push(new_item);
     }
 
-    pub fn draw(&mut self) {
-        set_color(ui::MENU_COLOR, ui::MENU_BGCOLOR);
+    /**
+     * start 번째 항목부터를 새 항목들로 바꾼다. 선택된 항목이 없어지면 마지막 항목을 선택한다.
+     */
+    pub fn replace_items_from(&mut self, start: usize, new_items: Vec<MenuItem>) {
+        self.items.truncate(start);
+        self.items.extend(new_items);
+        self.selected = self.selected.min(self.items.len().saturating_sub(1));
+    }
+
+    /**
+    상자와 항목을 그리고, 하위 메뉴가 열려 있으면 그 위에 하위 메뉴를 그린다.
+
+    # Arguments
+    * `state` - 명령별 항목 상태를 돌려주는 함수
+    * `theme` - 색 설정
+    * `screen_width`, `screen_height` - 하위 메뉴 위치를 정할 때 쓰는 화면 크기
+    */
+    pub fn draw(
+        &mut self,
+        state: &dyn Fn(ui::MenuCmd) -> ItemState,
+        theme: &Theme,
+        screen_width: u16,
+        screen_height: u16,
+    ) {
+        self.update_state(state);
+        set_color(theme.menu.fg, theme.menu.bg);
 
-        let x = self.index * 10 + 2;
-        let y = 1;
-        let w = 25;
-        let h = self.items.len() as u16 + 2;
+        let (x, y, w, h) = self.bounds();
         Rect::draw(&std::io::stdout(), x, y, w, h);
 
         for (i, item) in self.items.iter().enumerate() {
-            queue!(&std::io::stdout(), cursor::MoveTo(x + 2, y + i as u16 + 1)).unwrap();
-            if self.selected == i {
-                set_color(ui::MENU_COLOR, ui::MENU_BGCOLOR_SELECTED);
+            if item.separator {
+                check_result!(
+                    queue!(&std::io::stdout(), cursor::MoveTo(x, y + i as u16 + 1)),
+                    "MenuGroup::draw"
+                );
+                set_color(theme.menu.fg, theme.menu.bg);
+                print!("╟{}╢", "─".repeat(w as usize - 2));
+                continue;
+            }
+
+            check_result!(
+                queue!(&std::io::stdout(), cursor::MoveTo(x + 1, y + i as u16 + 1)),
+                "MenuGroup::draw"
+            );
+            let colors = match (self.selected == i, item.state.enabled) {
+                (true, true) => theme.menu_selected,
+                (true, false) => ColorPair::new(theme.menu_disabled.fg, theme.menu_selected.bg),
+                (false, true) => theme.menu,
+                (false, false) => theme.menu_disabled,
+            };
+            set_color(colors.fg, colors.bg);
+
+            // 체크 표시, 항목 이름은 왼쪽, 단축키(하위 메뉴 표시)는 오른쪽 정렬.
+            // 선택 표시가 줄 전체에 보이도록 공백으로 채운다.
+            let check = match item.state.checked {
+                Some(true) => ui::MENU_CHECK_MARK,
+                _ => ' ',
+            };
+            let right_label = item.right_label();
+            let padding = (w as usize - 2)
+                .saturating_sub(item.name.width_cjk() + right_label.width_cjk() + 4);
+            print!(" {} ", check);
+            if item.state.enabled {
+                draw_label(&item.name, item.mnemonic);
             } else {
-                set_color(ui::MENU_COLOR, ui::MENU_BGCOLOR);
+                print!("{}", item.name);
             }
-            print!("{}", item.name);
+            print!("{}{} ", " ".repeat(padding), right_label);
+        }
+
+        let bounds = self.bounds();
+        let row = y + self.selected as u16 + 1;
+        if let Some(submenu) = self.open_submenu_mut() {
+            submenu.layout_submenu(bounds, row, screen_width, screen_height);
+            submenu.draw(state, theme, screen_width, screen_height);
         }
     }
 }
@@ -1,16 +1,131 @@
+use super::menu_group::MenuGroup;
+use super::parse_mnemonic;
+use crate::consts::ui;
 use crate::consts::ui::MenuCmd;
+use crate::editor::command::{CommandId, CommandRegistry};
+use crate::editor::keymap::Keymap;
+use log::error;
+
+/**
+ * 메뉴를 그릴 때마다 에디터 상태로부터 계산하는 항목의 상태
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ItemState {
+    pub enabled: bool,
+    /// 체크 표시 항목이면 Some(체크 여부), 일반 항목이면 None
+    pub checked: Option<bool>,
+}
+
+impl ItemState {
+    pub fn enabled() -> ItemState {
+        ItemState {
+            enabled: true,
+            checked: None,
+        }
+    }
+}
 
 #[derive(Debug)]
 pub struct MenuItem {
     pub name: String,
     pub cmd: MenuCmd,
+    /// 밑줄로 표시되는 단축 글자의 위치 (글자 단위)
+    pub mnemonic: Option<usize>,
+    /// 오른쪽 정렬로 표시되는 단축키 (ex: "Ctrl+S")
+    pub shortcut: Option<String>,
+    pub separator: bool,
+    pub state: ItemState,
+    /// 선택하면 오른쪽에 열리는 하위 메뉴
+    pub submenu: Option<MenuGroup>,
 }
 
 impl MenuItem {
+    /**
+    # Arguments
+    * `new_name` - 항목 이름. '&' 바로 뒤의 글자가 단축 글자가 된다. (ex: "E&xit")
+    * `cmd_val` - 선택했을 때 실행할 명령
+    */
     pub fn new(new_name: &str, cmd_val: MenuCmd) -> MenuItem {
+        let (name, mnemonic) = parse_mnemonic(new_name);
         MenuItem {
-            name: String::from(new_name),
+            name,
             cmd: cmd_val,
+            mnemonic,
+            shortcut: None,
+            separator: false,
+            state: ItemState::enabled(),
+            submenu: None,
+        }
+    }
+
+    /**
+    명령 목록에 등록된 명령을 실행하는 항목. 이름은 명령에서, 단축키는 키 연결표에서 가져온다.
+
+    # Arguments
+    * `commands` - 에디터 명령 목록
+    * `keymap` - 키 연결표
+    * `id` - 명령 id
+    */
+    pub fn command(commands: &CommandRegistry, keymap: &Keymap, id: CommandId) -> MenuItem {
+        let name = match commands.get(id) {
+            Some(command) => command.name,
+            None => {
+                error!("Unknown command in menu: {}", id);
+                id
+            }
+        };
+
+        let mut item = MenuItem::new(name, MenuCmd::Run(id));
+        item.shortcut = keymap.shortcut_label(id);
+        item
+    }
+
+    /**
+    하위 메뉴를 여는 항목
+
+    # Arguments
+    * `new_name` - 항목 이름. '&' 바로 뒤의 글자가 단축 글자가 된다.
+    * `group` - 열릴 하위 메뉴
+    */
+    pub fn submenu(new_name: &str, group: MenuGroup) -> MenuItem {
+        let mut item = MenuItem::new(new_name, MenuCmd::None);
+        item.submenu = Some(group);
+        item
+    }
+
+    /**
+     * 항목 사이의 구분선. 선택할 수 없다.
+     */
+    pub fn separator() -> MenuItem {
+        MenuItem {
+            name: String::new(),
+            cmd: MenuCmd::None,
+            mnemonic: None,
+            shortcut: None,
+            separator: true,
+            state: ItemState {
+                enabled: false,
+                checked: None,
+            },
+            submenu: None,
+        }
+    }
+
+    /**
+     * 키보드/마우스로 고를 수 있는 항목인지 여부
+     */
+    pub fn selectable(&self) -> bool {
+        !self.separator && self.state.enabled
+    }
+
+    /**
+     * 항목 오른쪽에 표시할 문자열. 하위 메뉴가 있으면 하위 메뉴 표시, 아니면 단축키.
+     */
+    pub fn right_label(&self) -> &str {
+        match (&self.submenu, &self.shortcut) {
+            (Some(_), _) => ui::MENU_SUBMENU_MARK,
+            (None, Some(shortcut)) => shortcut,
+            (None, None) => "",
         }
     }
 }
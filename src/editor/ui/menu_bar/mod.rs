@@ -1,17 +1,30 @@
 mod menu_group;
 mod menu_item;
 
-use crate::consts::ui;
+pub use menu_item::ItemState;
+
+use crate::check_result;
 use crate::consts::ui::MenuCmd;
+use crate::editor::command::{CommandRegistry, WINDOW_LIST};
+use crate::editor::keymap::Keymap;
+use crate::editor::ui::theme::Theme;
 use crate::editor::util::set_color;
-use crossterm::event::{KeyCode, KeyModifiers};
-use crossterm::style::ResetColor;
+use crossterm::event::{KeyCode, KeyModifiers, MouseButton, MouseEventKind};
+use crossterm::style::{Attribute, ResetColor, SetAttribute};
 use crossterm::{cursor, queue};
 use log::{error, info};
-use menu_group::MenuGroup;
+use menu_group::{GroupAction, MenuGroup};
 use menu_item::MenuItem;
 use std::convert::TryInto;
 use std::io::Stdout;
+use unicode_width::UnicodeWidthStr;
+
+/// 첫 번째 그룹 이름의 가로 위치
+const NAME_START_X: usize = 2;
+/// 그룹 이름 사이의 간격
+const NAME_GAP: usize = 3;
+/// Window 메뉴에서 문서 목록 앞에 있는 항목 수 (문서 이동 2, 창 6, 구분선 2)
+const WINDOW_LIST_START: usize = 10;
 
 pub struct MenuBar {
     groups: Vec<MenuGroup>,
@@ -19,20 +32,106 @@ pub struct MenuBar {
 }
 
 impl MenuBar {
-    pub fn new() -> MenuBar {
+    /**
+    메뉴 막대를 만든다. 항목 이름은 명령 목록에서, 단축키는 키 연결표에서 가져온다.
+
+    # Arguments
+    * `commands` - 에디터 명령 목록
+    * `keymap` - 키 연결표
+    */
+    pub fn new(commands: &CommandRegistry, keymap: &Keymap) -> MenuBar {
         let mut menu_bar = MenuBar {
             groups: Vec::new(),
             selected: None,
         };
 
-        let mut file_group = MenuGroup::new("File", 0);
-        let exit_item = MenuItem::new("Exit", MenuCmd::Exit);
-        file_group.add_item(exit_item);
+        let mut line_ending_group = MenuGroup::new("Line &Endings");
+        line_ending_group.add_item(MenuItem::command(commands, keymap, "file.line_ending_lf"));
+        line_ending_group.add_item(MenuItem::command(commands, keymap, "file.line_ending_crlf"));
+        line_ending_group.add_item(MenuItem::command(commands, keymap, "file.line_ending_cr"));
+        line_ending_group.add_item(MenuItem::separator());
+        line_ending_group.add_item(MenuItem::command(commands, keymap, "file.final_newline"));
+
+        let mut encoding_group = MenuGroup::new("Save with Enco&ding");
+        for id in [
+            "file.encoding_utf8",
+            "file.encoding_utf8_bom",
+            "file.encoding_utf16le",
+            "file.encoding_utf16be",
+            "file.encoding_cp949",
+        ] {
+            encoding_group.add_item(MenuItem::command(commands, keymap, id));
+        }
+
+        let mut reopen_group = MenuGroup::new("&Reopen with Encoding");
+        for id in [
+            "file.reopen_utf8",
+            "file.reopen_utf16le",
+            "file.reopen_utf16be",
+            "file.reopen_cp949",
+        ] {
+            reopen_group.add_item(MenuItem::command(commands, keymap, id));
+        }
+
+        let mut file_group = MenuGroup::new("&File");
+        file_group.add_item(MenuItem::command(commands, keymap, "file.new"));
+        file_group.add_item(MenuItem::separator());
+        file_group.add_item(MenuItem::command(commands, keymap, "file.save"));
+        file_group.add_item(MenuItem::command(commands, keymap, "file.save_as"));
+        file_group.add_item(MenuItem::separator());
+        file_group.add_item(MenuItem::submenu("Line &Endings", line_ending_group));
+        file_group.add_item(MenuItem::submenu("Save with Enco&ding", encoding_group));
+        file_group.add_item(MenuItem::submenu("&Reopen with Encoding", reopen_group));
+        file_group.add_item(MenuItem::separator());
+        file_group.add_item(MenuItem::command(commands, keymap, "file.close"));
+        file_group.add_item(MenuItem::command(commands, keymap, "file.exit"));
         menu_bar.add_group(file_group);
 
-        let mut help_group = MenuGroup::new("Help", 1);
-        let about_item = MenuItem::new("About", MenuCmd::About);
-        help_group.add_item(about_item);
+        let mut edit_group = MenuGroup::new("&Edit");
+        edit_group.add_item(MenuItem::command(commands, keymap, "edit.undo"));
+        edit_group.add_item(MenuItem::command(commands, keymap, "edit.redo"));
+        menu_bar.add_group(edit_group);
+
+        let mut number_style_group = MenuGroup::new("Number &Style");
+        number_style_group.add_item(MenuItem::command(commands, keymap, "view.absolute_numbers"));
+        number_style_group.add_item(MenuItem::command(commands, keymap, "view.relative_numbers"));
+
+        let mut view_group = MenuGroup::new("&View");
+        view_group.add_item(MenuItem::command(commands, keymap, "view.word_wrap"));
+        view_group.add_item(MenuItem::command(commands, keymap, "view.line_numbers"));
+        view_group.add_item(MenuItem::submenu("Number &Style", number_style_group));
+        view_group.add_item(MenuItem::separator());
+        view_group.add_item(MenuItem::command(commands, keymap, "view.hex"));
+        menu_bar.add_group(view_group);
+
+        let mut options_group = MenuGroup::new("&Options");
+        options_group.add_item(MenuItem::command(commands, keymap, "options.display"));
+        options_group.add_item(MenuItem::command(
+            commands,
+            keymap,
+            "options.reload_settings",
+        ));
+        menu_bar.add_group(options_group);
+
+        let mut window_group = MenuGroup::new("&Window");
+        window_group.add_item(MenuItem::command(commands, keymap, "window.next"));
+        window_group.add_item(MenuItem::command(commands, keymap, "window.previous"));
+        window_group.add_item(MenuItem::separator());
+        for id in [
+            "window.split_horizontal",
+            "window.split_vertical",
+            "window.next_pane",
+            "window.grow_pane",
+            "window.shrink_pane",
+            "window.close_pane",
+        ] {
+            window_group.add_item(MenuItem::command(commands, keymap, id));
+        }
+        window_group.add_item(MenuItem::separator());
+        menu_bar.add_group(window_group);
+
+        let mut help_group = MenuGroup::new("&Help");
+        help_group.add_item(MenuItem::command(commands, keymap, "help.about"));
         menu_bar.add_group(help_group);
 
         menu_bar
@@ -42,44 +141,192 @@ impl MenuBar {
         self.groups.push(new_group);
     }
 
+    /**
+    Window 메뉴 아래쪽의 문서 목록을 다시 만든다. 앞에서부터 9개까지 번호를 붙여서 보여준다.
+
+    # Arguments
+    * `titles` - 열려 있는 문서 이름
+    */
+    pub fn set_window_list(&mut self, titles: &[&str]) {
+        let group = match self.groups.iter_mut().find(|group| group.name == "Window") {
+            Some(group) => group,
+            None => return,
+        };
+
+        let items = WINDOW_LIST
+            .iter()
+            .zip(titles.iter().enumerate())
+            .map(|(&id, (i, title))| {
+                // 파일 이름의 '&' 가 단축 글자 표시로 읽히지 않게 한다.
+                let name = format!("&{} {}", i + 1, title.replace('&', "&&"));
+                MenuItem::new(&name, MenuCmd::Run(id))
+            })
+            .collect();
+        group.replace_items_from(WINDOW_LIST_START, items);
+    }
+
+    /**
+    메뉴가 열려 있을 때의 키 입력 처리. 열린 그룹(과 하위 메뉴)이 먼저 처리하고,
+    그룹이 처리하지 않은 좌우 이동과 닫기는 메뉴 막대가 처리한다.
+    */
     pub fn handle_keyinput(&mut self, modifier: KeyModifiers, code: KeyCode) -> MenuCmd {
-        match (modifier, code) {
-            (KeyModifiers::NONE, KeyCode::Enter) => match self.selected {
-                Some(idx) => self.groups[idx].get_menu_cmd(),
+        let idx = match self.selected {
+            Some(idx) => idx,
+            None => return MenuCmd::None,
+        };
+
+        if let (KeyModifiers::ALT, KeyCode::Char(c)) = (modifier, code) {
+            return if self.open_by_mnemonic(c) {
+                MenuCmd::Refresh
+            } else {
+                MenuCmd::None
+            };
+        }
+
+        match self.groups[idx].handle_keyinput(modifier, code) {
+            GroupAction::Cmd(cmd) => cmd,
+            GroupAction::Close => match code {
+                KeyCode::Left => self.move_left(),
+                _ => MenuCmd::CloseMenu,
+            },
+            GroupAction::Unhandled => match (modifier, code) {
+                (KeyModifiers::NONE, KeyCode::Right) => self.move_right(),
                 _ => MenuCmd::None,
             },
-            (KeyModifiers::NONE, KeyCode::Left) => self.move_left(),
-            (KeyModifiers::NONE, KeyCode::Right) => self.move_right(),
-            (KeyModifiers::NONE, KeyCode::Esc) => MenuCmd::CloseMenu,
-            _ => MenuCmd::None,
         }
     }
 
-    pub fn draw(&mut self, mut screen: &Stdout, width: usize) {
+    /**
+    Alt+글자로 메뉴 그룹을 연다.
+
+    # Return
+    * 단축 글자가 일치하는 그룹이 있었는지 여부
+    */
+    pub fn open_by_mnemonic(&mut self, ch: char) -> bool {
+        let ch = ch.to_ascii_lowercase();
+        match self
+            .groups
+            .iter()
+            .position(|group| mnemonic_char(&group.name, group.mnemonic) == Some(ch))
+        {
+            Some(idx) => {
+                self.select_group(idx);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /**
+     * 메뉴 그룹을 연다. 그 그룹에서 열려 있던 하위 메뉴는 닫는다.
+     */
+    fn select_group(&mut self, idx: usize) {
+        self.groups[idx].reset();
+        self.selected = Some(idx);
+    }
+
+    /**
+    메뉴 막대의 마우스 입력 처리. 왼쪽 버튼을 누른 것만 처리한다.
+
+    # Arguments
+    * `kind` - 마우스 이벤트 종류
+    * `x`, `y` - 클릭한 화면 위치
+    */
+    pub fn handle_mouse(&mut self, kind: MouseEventKind, x: u16, y: u16) -> MenuCmd {
+        if kind != MouseEventKind::Down(MouseButton::Left) {
+            return MenuCmd::None;
+        }
+
+        if y == 0 {
+            return match self.group_at(x) {
+                Some(idx) if self.selected == Some(idx) => MenuCmd::CloseMenu,
+                Some(idx) => {
+                    self.select_group(idx);
+                    MenuCmd::Refresh
+                }
+                None if self.selected.is_some() => MenuCmd::CloseMenu,
+                None => MenuCmd::None,
+            };
+        }
+
+        match self.selected {
+            Some(idx) => self.groups[idx]
+                .handle_mouse(x, y)
+                .unwrap_or(MenuCmd::CloseMenu),
+            None => MenuCmd::None,
+        }
+    }
+
+    /**
+     * 메뉴 막대에서 x 위치에 이름이 그려진 메뉴 그룹의 인덱스
+     */
+    fn group_at(&self, x: u16) -> Option<usize> {
+        (0..self.groups.len()).find(|&idx| {
+            let name_x = self.name_x(idx);
+            x >= name_x && x < name_x + self.groups[idx].name.width_cjk() as u16
+        })
+    }
+
+    /**
+     * 메뉴 막대에서 idx 번째 그룹 이름의 가로 위치. 앞쪽 이름들의 실제 표시 너비로 계산한다.
+     */
+    fn name_x(&self, idx: usize) -> u16 {
+        let x: usize = self.groups[..idx]
+            .iter()
+            .map(|group| group.name.width_cjk() + NAME_GAP)
+            .sum();
+
+        (x + NAME_START_X).try_into().unwrap_or(u16::MAX)
+    }
+
+    /**
+    메뉴 막대와 열려 있는 메뉴 그룹을 그린다.
+
+    # Arguments
+    * `screen` - 콘솔 화면 객체
+    * `width`, `height` - 화면 크기
+    * `state` - 명령별 항목 상태(활성, 체크)를 돌려주는 함수. 그룹을 그릴 때마다 다시 계산한다.
+    * `theme` - 색 설정
+    */
+    pub fn draw(
+        &mut self,
+        mut screen: &Stdout,
+        width: usize,
+        height: u16,
+        state: &dyn Fn(MenuCmd) -> ItemState,
+        theme: &Theme,
+    ) {
         info!("draw menubar: groups = {:?} / {:?}", self.groups, screen);
 
-        self.draw_empty_background(screen, width);
+        self.draw_empty_background(screen, width, theme);
 
         for (iter, group) in self.groups.iter().enumerate() {
-            self.draw_name(screen, iter, &group.name);
+            self.draw_name(screen, iter, group);
         }
 
         match self.selected {
             Some(idx) => {
                 info!("some selected: {}", idx);
-                set_color(ui::MENU_COLOR, ui::MENU_BGCOLOR_SELECTED);
-                self.draw_name(screen, idx, &self.groups[idx].name);
-                self.groups[idx].draw();
+                set_color(theme.menu_selected.fg, theme.menu_selected.bg);
+                self.draw_name(screen, idx, &self.groups[idx]);
+
+                let name_x = self.name_x(idx);
+                let width = width.try_into().unwrap_or(u16::MAX);
+                self.groups[idx].layout(name_x, width);
+                self.groups[idx].draw(state, theme, width, height);
             }
             None => info!("Not selected"),
         }
 
-        queue!(screen, ResetColor).unwrap();
+        check_result!(queue!(screen, ResetColor), "Failed to reset color");
     }
 
-    fn draw_empty_background(&self, mut screen: &Stdout, width: usize) {
-        queue!(screen, cursor::MoveTo(0, 0)).unwrap();
-        set_color(ui::MENU_COLOR, ui::MENU_BGCOLOR);
+    fn draw_empty_background(&self, mut screen: &Stdout, width: usize, theme: &Theme) {
+        check_result!(
+            queue!(screen, cursor::MoveTo(0, 0)),
+            "Failed to move cursor (menu bar)"
+        );
+        set_color(theme.menu.fg, theme.menu.bg);
 
         for _ in 0..width {
             print!(" ");
@@ -92,12 +339,11 @@ impl MenuBar {
     # Arguments
     * `screen` - 콘솔 화면 객체
     * `idx` - 가로 위치(0이 왼쪽)
-    * `name` - 이름 문자열
+    * `group` - 이름을 그릴 메뉴 그룹
     */
-    fn draw_name(&self, mut screen: &Stdout, idx: usize, name: &String) {
-        let x = (idx * 10 + 4).try_into().unwrap_or(0);
-        match queue!(screen, cursor::MoveTo(x, 0)) {
-            Ok(_) => print!("{}", name),
+    fn draw_name(&self, mut screen: &Stdout, idx: usize, group: &MenuGroup) {
+        match queue!(screen, cursor::MoveTo(self.name_x(idx), 0)) {
+            Ok(_) => draw_label(&group.name, group.mnemonic),
             Err(e) => error!("draw_name error: {}", e),
         }
     }
@@ -114,7 +360,7 @@ impl MenuBar {
                 idx - 1
             };
 
-            self.selected = Some(new_idx)
+            self.select_group(new_idx)
         }
         info!("left - selected 2 = {:?}", self.selected);
 
@@ -128,9 +374,271 @@ impl MenuBar {
             } else {
                 idx + 1
             };
-            self.selected = Some(new_idx)
+            self.select_group(new_idx)
         }
 
         MenuCmd::Refresh
     }
 }
+
+/**
+메뉴 이름에서 '&' 를 빼고, '&' 바로 뒤 글자의 위치를 구한다. "&&" 는 '&' 글자 자체.
+
+# Return
+* ('&' 를 뺀 이름, 단축 글자의 위치(글자 단위))
+*/
+pub fn parse_mnemonic(label: &str) -> (String, Option<usize>) {
+    let mut name = String::new();
+    let mut mnemonic = None;
+    let mut chars = label.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        if c == '&' {
+            match chars.next() {
+                Some('&') => name.push('&'),
+                Some(next) => {
+                    if mnemonic.is_none() {
+                        mnemonic = Some(name.chars().count());
+                    }
+                    name.push(next);
+                }
+                None => {}
+            }
+        } else {
+            name.push(c);
+        }
+    }
+
+    (name, mnemonic)
+}
+
+/**
+ * 단축 글자 (소문자)
+ */
+pub fn mnemonic_char(name: &str, mnemonic: Option<usize>) -> Option<char> {
+    mnemonic
+        .and_then(|idx| name.chars().nth(idx))
+        .map(|c| c.to_ascii_lowercase())
+}
+
+/**
+ * 현재 커서 위치에 이름을 출력한다. 단축 글자에는 밑줄을 긋는다.
+ */
+pub fn draw_label(name: &str, mnemonic: Option<usize>) {
+    for (i, c) in name.chars().enumerate() {
+        if Some(i) == mnemonic {
+            check_result!(
+                queue!(std::io::stdout(), SetAttribute(Attribute::Underlined)),
+                "draw_label"
+            );
+            print!("{}", c);
+            check_result!(
+                queue!(std::io::stdout(), SetAttribute(Attribute::NoUnderline)),
+                "draw_label"
+            );
+        } else {
+            print!("{}", c);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn new_menu_bar() -> MenuBar {
+        let commands = CommandRegistry::new();
+        MenuBar::new(&commands, &Keymap::new(&commands))
+    }
+
+    #[test]
+    fn test_parse_mnemonic() {
+        assert_eq!(parse_mnemonic("&File"), (String::from("File"), Some(0)));
+        assert_eq!(parse_mnemonic("E&xit"), (String::from("Exit"), Some(1)));
+        assert_eq!(parse_mnemonic("About"), (String::from("About"), None));
+        assert_eq!(parse_mnemonic("A&&B"), (String::from("A&B"), None));
+        assert_eq!(
+            parse_mnemonic("파일(&F)"),
+            (String::from("파일(F)"), Some(3))
+        );
+    }
+
+    #[test]
+    fn test_keyinput() {
+        let mut menu_bar = new_menu_bar();
+        assert!(menu_bar.open_by_mnemonic('F'));
+        menu_bar.groups[0].update_state(&|_| ItemState::enabled());
+        assert_eq!(menu_bar.selected, Some(0));
+
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('x')),
+            MenuCmd::Run("file.exit")
+        );
+
+        // 구분선은 건너뛰고, 하위 메뉴 항목은 Enter 로 하위 메뉴를 연다
+        for _ in 0..2 {
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Up);
+        }
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Refresh
+        );
+        menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Esc);
+        for _ in 0..3 {
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Up);
+        }
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Run("file.save_as")
+        );
+
+        menu_bar.handle_keyinput(KeyModifiers::ALT, KeyCode::Char('h'));
+        assert_eq!(menu_bar.selected, Some(5));
+    }
+
+    #[test]
+    fn test_window_list() {
+        let mut menu_bar = new_menu_bar();
+        menu_bar.set_window_list(&["memo.md", "R&D.md"]);
+        assert!(menu_bar.open_by_mnemonic('w'));
+        menu_bar.groups[4].update_state(&|_| ItemState::enabled());
+
+        // 문서 이름의 '&' 는 단축 글자가 아니다.
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('2')),
+            MenuCmd::Run("window.document_2")
+        );
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('d')),
+            MenuCmd::None
+        );
+
+        // 문서가 줄어들면 목록도 줄어든다.
+        menu_bar.set_window_list(&["memo.md"]);
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('2')),
+            MenuCmd::None
+        );
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('1')),
+            MenuCmd::Run("window.document_1")
+        );
+    }
+
+    #[test]
+    fn test_submenu() {
+        let mut inner = MenuGroup::new("&Inner");
+        inner.add_item(MenuItem::new("&About", MenuCmd::Run("help.about")));
+
+        let mut middle = MenuGroup::new("&Middle");
+        middle.add_item(MenuItem::new("&Save", MenuCmd::Run("file.save")));
+        middle.add_item(MenuItem::submenu("&Inner", inner));
+
+        let mut top = MenuGroup::new("&Top");
+        top.add_item(MenuItem::new("E&xit", MenuCmd::Run("file.exit")));
+        top.add_item(MenuItem::submenu("&Middle", middle));
+
+        let mut menu_bar = MenuBar {
+            groups: Vec::new(),
+            selected: None,
+        };
+        menu_bar.add_group(top);
+        menu_bar.add_group(MenuGroup::new("&Other"));
+        assert!(menu_bar.open_by_mnemonic('t'));
+
+        // 단축 글자와 Right 로 하위 메뉴를 연다
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('m')),
+            MenuCmd::Refresh
+        );
+        menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Down);
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Right),
+            MenuCmd::Refresh
+        );
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Run("help.about")
+        );
+
+        // Left/Esc 는 가장 안쪽 하위 메뉴만 닫는다
+        menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Left);
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Up),
+            MenuCmd::Refresh
+        );
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Run("file.save")
+        );
+        menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Esc);
+        assert_eq!(menu_bar.selected, Some(0));
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('x')),
+            MenuCmd::Run("file.exit")
+        );
+
+        // 맨 위 그룹에서 Esc 는 메뉴 전체를 닫는다
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Esc),
+            MenuCmd::CloseMenu
+        );
+    }
+
+    #[test]
+    fn test_layout() {
+        let mut menu_bar = MenuBar {
+            groups: Vec::new(),
+            selected: None,
+        };
+        menu_bar.add_group(MenuGroup::new("&File"));
+        menu_bar.add_group(MenuGroup::new("편집(&E)"));
+        menu_bar.add_group(MenuGroup::new("&Help"));
+
+        assert_eq!(menu_bar.name_x(0), 2);
+        assert_eq!(menu_bar.name_x(1), 9);
+        assert_eq!(menu_bar.name_x(2), 19);
+        assert_eq!(menu_bar.group_at(10), Some(1));
+        assert_eq!(menu_bar.group_at(17), None);
+
+        let mut group = MenuGroup::new("&Help");
+        let commands = CommandRegistry::new();
+        let keymap = Keymap::new(&commands);
+        group.add_item(MenuItem::command(&commands, &keymap, "help.about"));
+        assert_eq!(group.width(), 15);
+
+        // 오른쪽 끝을 넘으면 왼쪽으로 민다
+        group.layout(19, 80);
+        assert_eq!(group.item_at(18, 2), None);
+        assert_eq!(group.item_at(19, 2), Some(0));
+        group.layout(75, 80);
+        assert_eq!(group.item_at(66, 2), Some(0));
+        assert_eq!(group.item_at(65, 2), None);
+    }
+
+    #[test]
+    fn test_disabled_items() {
+        let mut menu_bar = new_menu_bar();
+        assert!(menu_bar.open_by_mnemonic('f'));
+
+        // Exit 만 활성: 선택이 Exit 으로 옮겨지고, 단축 글자와 방향키로 다른 항목을 고를 수 없다.
+        menu_bar.groups[0].update_state(&|cmd| ItemState {
+            enabled: cmd == MenuCmd::Run("file.exit"),
+            checked: None,
+        });
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Run("file.exit")
+        );
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Char('s')),
+            MenuCmd::None
+        );
+
+        menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Up);
+        assert_eq!(
+            menu_bar.handle_keyinput(KeyModifiers::NONE, KeyCode::Enter),
+            MenuCmd::Run("file.exit")
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use crate::check_result;
 use crossterm::{cursor, queue};
 use std::io::Stdout;
 
@@ -10,8 +11,35 @@ impl Rect {
         Rect::draw_bottom_line(screen, x, y, w, h);
     }
 
+    /**
+     * 세로 구분선. 색은 부르는 쪽에서 정한다.
+     */
+    pub fn draw_vertical_line(mut screen: &Stdout, x: u16, y: u16, h: u16) {
+        for i in y..(y + h) {
+            check_result!(
+                queue!(screen, cursor::MoveTo(x, i)),
+                "Failed to move cursor (vertical line)"
+            );
+            print!("│");
+        }
+    }
+
+    /**
+     * 가로 구분선. 색은 부르는 쪽에서 정한다.
+     */
+    pub fn draw_horizontal_line(mut screen: &Stdout, x: u16, y: u16, w: u16) {
+        check_result!(
+            queue!(screen, cursor::MoveTo(x, y)),
+            "Failed to move cursor (horizontal line)"
+        );
+        print!("{}", "─".repeat(w as usize));
+    }
+
     fn draw_top_line(mut screen: &Stdout, x: u16, y: u16, w: u16) {
-        queue!(screen, cursor::MoveTo(x, y)).expect("draw_top_line failed");
+        check_result!(
+            queue!(screen, cursor::MoveTo(x, y)),
+            "Failed to move cursor (top line)"
+        );
 
         print!("╔");
         for _ in 2..(w) {
@@ -22,7 +50,10 @@ impl Rect {
 
     fn draw_mid_lines(mut screen: &Stdout, x: u16, y: u16, w: u16, h: u16) {
         for i in (y + 1)..(y + h - 1) {
-            queue!(screen, cursor::MoveTo(x, i)).expect("Failed to move cursor (simple_dialog)");
+            check_result!(
+                queue!(screen, cursor::MoveTo(x, i)),
+                "Failed to move cursor (mid lines)"
+            );
             print!("║");
             for _ in (x + 1)..(x + w - 1) {
                 print!(" ");
@@ -32,8 +63,10 @@ impl Rect {
     }
 
     fn draw_bottom_line(mut screen: &Stdout, x: u16, y: u16, w: u16, h: u16) {
-        queue!(screen, cursor::MoveTo(x, y + h - 1))
-            .expect("Failed to move cursor (simple_dialog)");
+        check_result!(
+            queue!(screen, cursor::MoveTo(x, y + h - 1)),
+            "Failed to move cursor (bottom line)"
+        );
         print!("╚");
         for _ in 2..(w as i32) {
             print!("═");
@@ -0,0 +1,395 @@
+use crate::editor::ui::rect::Rect;
+use std::io::Stdout;
+
+/// 나눈 창 하나의 최소 크기. 이보다 작아지면 나누지 않고, 구분선도 이보다 작아지게 옮기지 않는다.
+const MIN_ROWS: u16 = 2;
+const MIN_COLUMNS: u16 = 10;
+/// 첫 번째 창이 차지하는 비율(%)의 범위
+const MIN_PERCENT: i16 = 10;
+const MAX_PERCENT: i16 = 90;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SplitDirection {
+    /// 위아래로 나눈다. 가로 구분선
+    Horizontal,
+    /// 좌우로 나눈다. 세로 구분선
+    Vertical,
+}
+
+/**
+ * 화면의 사각형 영역
+ */
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Area {
+    pub x: u16,
+    pub y: u16,
+    pub w: u16,
+    pub h: u16,
+}
+
+impl Area {
+    pub fn contains(&self, x: u16, y: u16) -> bool {
+        x >= self.x && x < self.x + self.w && y >= self.y && y < self.y + self.h
+    }
+
+    /**
+     * 이 영역을 나눌 공간이 있는지 여부
+     */
+    pub fn can_split(&self, direction: SplitDirection) -> bool {
+        match direction {
+            SplitDirection::Horizontal => self.h > MIN_ROWS * 2,
+            SplitDirection::Vertical => self.w > MIN_COLUMNS * 2,
+        }
+    }
+
+    /**
+     * 구분선을 뺀 크기와 그 방향으로 나눈 창 하나의 최소 크기
+     */
+    fn split_size(&self, direction: SplitDirection) -> (u16, u16) {
+        match direction {
+            SplitDirection::Horizontal => (self.h.saturating_sub(1), MIN_ROWS),
+            SplitDirection::Vertical => (self.w.saturating_sub(1), MIN_COLUMNS),
+        }
+    }
+
+    /**
+     * 첫 번째 영역, 구분선, 두 번째 영역으로 나눈다.
+     * 화면이 줄어도 두 영역 모두 최소 크기 이상이 되도록 하고, 그럴 공간이 없으면 한 칸 이상만 남긴다.
+     *
+     * # Arguments
+     * * `percent` - 구분선을 뺀 크기 중 첫 번째 영역이 차지하는 비율
+     */
+    fn split(&self, direction: SplitDirection, percent: u16) -> (Area, Area, Area) {
+        let (size, min) = self.split_size(direction);
+        let min = if size >= min * 2 { min } else { 1 };
+        let first = ((size as u32 * percent as u32 + 50) / 100) as u16;
+        let first = first.max(min).min(size.saturating_sub(min));
+        let second = size - first;
+
+        match direction {
+            SplitDirection::Horizontal => (
+                Area { h: first, ..*self },
+                Area {
+                    y: self.y + first,
+                    h: 1,
+                    ..*self
+                },
+                Area {
+                    y: self.y + first + 1,
+                    h: second,
+                    ..*self
+                },
+            ),
+            SplitDirection::Vertical => (
+                Area { w: first, ..*self },
+                Area {
+                    x: self.x + first,
+                    w: 1,
+                    ..*self
+                },
+                Area {
+                    x: self.x + first + 1,
+                    w: second,
+                    ..*self
+                },
+            ),
+        }
+    }
+}
+
+/**
+편집 영역을 나눈 모양. 창은 0부터 붙인 번호로 가리킨다.
+창 하나를 나누면 그 자리가 두 창을 가진 Split 으로 바뀐다.
+*/
+#[derive(Debug, PartialEq)]
+pub enum Layout {
+    Pane(usize),
+    Split {
+        direction: SplitDirection,
+        /// 구분선을 뺀 크기 중 첫 번째 쪽이 차지하는 비율(%)
+        percent: u16,
+        first: Box<Layout>,
+        second: Box<Layout>,
+    },
+}
+
+impl Layout {
+    /**
+     * `pane` 을 나눠서 두 번째(아래 또는 오른쪽) 자리에 `new_pane` 을 둔다.
+     */
+    pub fn split(&mut self, pane: usize, direction: SplitDirection, new_pane: usize) -> bool {
+        match self {
+            Layout::Pane(id) if *id == pane => {
+                *self = Layout::Split {
+                    direction,
+                    percent: 50,
+                    first: Box::new(Layout::Pane(pane)),
+                    second: Box::new(Layout::Pane(new_pane)),
+                };
+                true
+            }
+            Layout::Pane(_) => false,
+            Layout::Split { first, second, .. } => {
+                first.split(pane, direction, new_pane) || second.split(pane, direction, new_pane)
+            }
+        }
+    }
+
+    /**
+     * 창 하나를 없애고 그 옆의 창이 자리를 차지하게 한다.
+     * 뒤에 있던 창 번호는 하나씩 당긴다. (에디터의 창 목록에서 지운 것과 맞춘다)
+     */
+    pub fn remove(&mut self, pane: usize) -> bool {
+        if !self.remove_pane(pane) {
+            return false;
+        }
+        self.renumber(pane);
+        true
+    }
+
+    fn remove_pane(&mut self, pane: usize) -> bool {
+        let sibling = match self {
+            Layout::Pane(_) => return false,
+            Layout::Split { first, second, .. } => {
+                if **first == Layout::Pane(pane) {
+                    std::mem::replace(&mut **second, Layout::Pane(0))
+                } else if **second == Layout::Pane(pane) {
+                    std::mem::replace(&mut **first, Layout::Pane(0))
+                } else {
+                    return first.remove_pane(pane) || second.remove_pane(pane);
+                }
+            }
+        };
+        *self = sibling;
+        true
+    }
+
+    fn renumber(&mut self, removed: usize) {
+        match self {
+            Layout::Pane(id) => {
+                if *id > removed {
+                    *id -= 1;
+                }
+            }
+            Layout::Split { first, second, .. } => {
+                first.renumber(removed);
+                second.renumber(removed);
+            }
+        }
+    }
+
+    fn contains(&self, pane: usize) -> bool {
+        match self {
+            Layout::Pane(id) => *id == pane,
+            Layout::Split { first, second, .. } => first.contains(pane) || second.contains(pane),
+        }
+    }
+
+    /**
+     * `pane` 을 바로 둘러싼 구분선을 옮겨서 그 창을 키우거나 줄인다.
+     * 양쪽 창이 최소 크기(MIN_ROWS, MIN_COLUMNS)보다 작아지게는 옮기지 않는다.
+     *
+     * # Arguments
+     * * `area` - 나누기 전의 영역
+     * * `delta` - 바꿀 비율(%). 음수면 줄인다.
+     */
+    pub fn resize(&mut self, area: Area, pane: usize, delta: i16) -> bool {
+        match self {
+            Layout::Pane(_) => false,
+            Layout::Split {
+                direction,
+                percent,
+                first,
+                second,
+            } => {
+                let (first_area, _, second_area) = area.split(*direction, *percent);
+                if first.resize(first_area, pane, delta) || second.resize(second_area, pane, delta)
+                {
+                    return true;
+                }
+                let delta = if first.contains(pane) {
+                    delta
+                } else if second.contains(pane) {
+                    -delta
+                } else {
+                    return false;
+                };
+
+                // 양쪽 모두 최소 크기가 되는 비율. 공간이 모자라면 옮기지 않는다.
+                let (size, min) = area.split_size(*direction);
+                let lowest = (min as u32 * 100 + size as u32 - 1) / (size as u32).max(1);
+                let lowest = (lowest as i16).max(MIN_PERCENT);
+                let highest = (100 - lowest).min(MAX_PERCENT);
+                if lowest <= highest {
+                    *percent = (*percent as i16 + delta).clamp(lowest, highest) as u16;
+                }
+                true
+            }
+        }
+    }
+
+    /**
+     * 창마다 차지하는 영역. 창 번호 순서로 돌려준다.
+     */
+    pub fn areas(&self, area: Area) -> Vec<Area> {
+        let mut areas = Vec::new();
+        self.collect_areas(area, &mut areas);
+        areas.sort_by_key(|(pane, _)| *pane);
+        areas.into_iter().map(|(_, area)| area).collect()
+    }
+
+    fn collect_areas(&self, area: Area, areas: &mut Vec<(usize, Area)>) {
+        match self {
+            Layout::Pane(pane) => areas.push((*pane, area)),
+            Layout::Split {
+                direction,
+                percent,
+                first,
+                second,
+            } => {
+                let (first_area, _, second_area) = area.split(*direction, *percent);
+                first.collect_areas(first_area, areas);
+                second.collect_areas(second_area, areas);
+            }
+        }
+    }
+
+    /**
+     * 창 사이의 구분선을 그린다. 색은 부르는 쪽에서 정한다.
+     */
+    pub fn draw_separators(&self, screen: &Stdout, area: Area) {
+        if let Layout::Split {
+            direction,
+            percent,
+            first,
+            second,
+        } = self
+        {
+            let (first_area, line, second_area) = area.split(*direction, *percent);
+            match direction {
+                SplitDirection::Horizontal => {
+                    Rect::draw_horizontal_line(screen, line.x, line.y, line.w)
+                }
+                SplitDirection::Vertical => {
+                    Rect::draw_vertical_line(screen, line.x, line.y, line.h)
+                }
+            }
+            first.draw_separators(screen, first_area);
+            second.draw_separators(screen, second_area);
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const SCREEN: Area = Area {
+        x: 0,
+        y: 1,
+        w: 80,
+        h: 22,
+    };
+
+    #[test]
+    fn test_areas() {
+        let mut layout = Layout::Pane(0);
+        assert_eq!(layout.areas(SCREEN), vec![SCREEN]);
+
+        assert!(layout.split(0, SplitDirection::Horizontal, 1));
+        assert!(layout.split(1, SplitDirection::Vertical, 2));
+        assert!(!layout.split(5, SplitDirection::Vertical, 3));
+        assert_eq!(
+            layout.areas(SCREEN),
+            vec![
+                Area {
+                    x: 0,
+                    y: 1,
+                    w: 80,
+                    h: 11
+                },
+                Area {
+                    x: 0,
+                    y: 13,
+                    w: 40,
+                    h: 10
+                },
+                Area {
+                    x: 41,
+                    y: 13,
+                    w: 39,
+                    h: 10
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_resize() {
+        let mut layout = Layout::Pane(0);
+        layout.split(0, SplitDirection::Vertical, 1);
+        layout.split(1, SplitDirection::Horizontal, 2);
+
+        // 1 은 위아래로 나눈 것 중 위쪽이므로 가로 구분선이 내려간다.
+        assert!(layout.resize(SCREEN, 1, 20));
+        let areas = layout.areas(SCREEN);
+        assert_eq!(areas[0].w, 40);
+        assert_eq!((areas[1].h, areas[2].h), (15, 6));
+
+        // 0 은 왼쪽이므로 세로 구분선이 왼쪽으로 간다. 창은 MIN_COLUMNS 보다 작아지지 않는다.
+        assert!(layout.resize(SCREEN, 0, -100));
+        assert_eq!(layout.areas(SCREEN)[0].w, MIN_COLUMNS);
+        // 아래쪽 창도 MIN_ROWS 보다 작아지지 않는다.
+        assert!(layout.resize(SCREEN, 1, 100));
+        assert_eq!(layout.areas(SCREEN)[2].h, MIN_ROWS);
+
+        assert!(!layout.resize(SCREEN, 3, 10));
+        assert!(!Layout::Pane(0).resize(SCREEN, 0, 10));
+    }
+
+    #[test]
+    fn test_min_size() {
+        let mut layout = Layout::Pane(0);
+        layout.split(0, SplitDirection::Vertical, 1);
+        assert!(layout.resize(SCREEN, 0, -40));
+
+        // 화면이 줄어도 비율만큼이 아니라 최소 크기를 남긴다.
+        let narrow = Area { w: 41, ..SCREEN };
+        let areas = layout.areas(narrow);
+        assert_eq!((areas[0].w, areas[1].w), (MIN_COLUMNS, 30));
+
+        // 최소 크기 둘도 들어가지 않으면 한 칸 이상만 남긴다.
+        let areas = layout.areas(Area { w: 12, ..SCREEN });
+        assert_eq!((areas[0].w, areas[1].w), (1, 10));
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut layout = Layout::Pane(0);
+        layout.split(0, SplitDirection::Vertical, 1);
+        layout.split(0, SplitDirection::Horizontal, 2);
+
+        assert!(layout.remove(0));
+        // 0 이 있던 자리를 2(→1)가 차지한다.
+        assert_eq!(
+            layout,
+            Layout::Split {
+                direction: SplitDirection::Vertical,
+                percent: 50,
+                first: Box::new(Layout::Pane(1)),
+                second: Box::new(Layout::Pane(0)),
+            }
+        );
+
+        assert!(layout.remove(1));
+        assert_eq!(layout, Layout::Pane(0));
+        assert!(!layout.remove(0));
+    }
+
+    #[test]
+    fn test_can_split() {
+        assert!(SCREEN.can_split(SplitDirection::Vertical));
+        assert!(!Area { w: 20, ..SCREEN }.can_split(SplitDirection::Vertical));
+        assert!(!Area { h: 4, ..SCREEN }.can_split(SplitDirection::Horizontal));
+    }
+}
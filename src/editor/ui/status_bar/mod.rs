@@ -0,0 +1,179 @@
+use crate::editor::ui::theme::Theme;
+use crate::editor::util::set_color;
+use crossterm::style::ResetColor;
+use crossterm::{cursor, queue};
+use log::error;
+use std::io::Stdout;
+use unicode_width::UnicodeWidthStr;
+
+/**
+ * 상태 표시줄에 출력할 정보. 에디터가 그릴 때마다 새로 만들어서 넘긴다.
+ */
+pub struct StatusInfo<'a> {
+    pub filename: Option<&'a str>,
+    pub modified: bool,
+    pub read_only: bool,
+    pub line: usize,
+    pub total_lines: usize,
+    pub column: usize,
+    pub char_index: usize,
+    pub encoding: &'a str,
+    pub line_ending: &'a str,
+    pub overwrite: bool,
+    /// View > Hex 에서의 커서 위치와 전체 바이트 수. 있으면 줄/칸 대신 보여준다.
+    pub hex_offset: Option<(usize, usize)>,
+    /// 입력 중인 여러 키 조합 (ex: "Ctrl+K"). 없으면 빈 문자열
+    pub pending_keys: &'a str,
+    /// 잠깐 보여주는 알림 (ex: "Saved memo.md"). 있으면 파일 이름 대신 보여준다.
+    pub message: &'a str,
+}
+
+pub struct StatusBar {}
+
+impl StatusBar {
+    /**
+        화면 가장 아래 줄에 상태 표시줄을 그린다.
+
+        # Arguments
+        * `screen` - 콘솔 화면 객체
+        * `y` - 그릴 세로 위치
+        * `width` - 화면 너비
+        * `info` - 출력할 정보
+        * `theme` - 색 설정
+    */
+    pub fn draw(mut screen: &Stdout, y: u16, width: usize, info: &StatusInfo, theme: &Theme) {
+        if let Err(e) = queue!(screen, cursor::MoveTo(0, y)) {
+            error!("StatusBar::draw: {}", e);
+            return;
+        }
+
+        set_color(theme.status.fg, theme.status.bg);
+        print!("{}", StatusBar::format(width, info));
+
+        if let Err(e) = queue!(screen, ResetColor) {
+            error!("StatusBar::draw: {}", e);
+        }
+    }
+
+    /**
+        상태 표시줄 문자열을 만든다. 왼쪽에는 파일 이름, 오른쪽에는 위치 등의 정보를 붙이고
+        가운데는 공백으로 채워서 정확히 `width` 너비가 되도록 한다.
+    */
+    pub fn format(width: usize, info: &StatusInfo) -> String {
+        let left = if info.message.is_empty() {
+            format!(
+                " {}{}{}",
+                info.filename.unwrap_or("Untitled"),
+                if info.modified { " *" } else { "" },
+                if info.read_only { " [Read-only]" } else { "" }
+            )
+        } else {
+            format!(" {}", info.message)
+        };
+        let pending = if info.pending_keys.is_empty() {
+            String::new()
+        } else {
+            format!("{} | ", info.pending_keys)
+        };
+        let right = match info.hex_offset {
+            Some((offset, len)) => format!("{}Offset 0x{:X}/0x{:X} | HEX ", pending, offset, len),
+            None => format!(
+                "{}Ln {}/{}, Col {} (Ch {}) | {} | {} | {} ",
+                pending,
+                info.line,
+                info.total_lines,
+                info.column,
+                info.char_index,
+                info.encoding,
+                info.line_ending,
+                if info.overwrite { "OVR" } else { "INS" }
+            ),
+        };
+
+        let used = left.width_cjk() + right.width_cjk();
+        if used < width {
+            format!("{}{}{}", left, " ".repeat(width - used), right)
+        } else {
+            // 공간이 모자라면 위치 정보를 우선해서 보여준다.
+            let mut s = String::new();
+            for c in right.chars().rev() {
+                if s.width_cjk() + 1 > width {
+                    break;
+                }
+                s.insert(0, c);
+            }
+            s
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn sample() -> StatusInfo<'static> {
+        StatusInfo {
+            filename: Some("메모.md"),
+            modified: true,
+            read_only: false,
+            line: 3,
+            total_lines: 10,
+            column: 5,
+            char_index: 3,
+            encoding: "UTF-8",
+            line_ending: "LF",
+            overwrite: false,
+            hex_offset: None,
+            pending_keys: "",
+            message: "",
+        }
+    }
+
+    #[test]
+    fn test_format_width() {
+        let s = StatusBar::format(80, &sample());
+        assert_eq!(s.width_cjk(), 80);
+        assert!(s.starts_with(" 메모.md *"));
+        assert!(s.ends_with("Ln 3/10, Col 5 (Ch 3) | UTF-8 | LF | INS "));
+    }
+
+    #[test]
+    fn test_format_narrow() {
+        let s = StatusBar::format(10, &sample());
+        assert!(s.width_cjk() <= 10);
+        assert!(s.ends_with("INS "));
+    }
+
+    #[test]
+    fn test_format_pending_keys() {
+        let info = StatusInfo {
+            pending_keys: "Ctrl+K",
+            ..sample()
+        };
+        let s = StatusBar::format(80, &info);
+        assert!(s.ends_with("Ctrl+K | Ln 3/10, Col 5 (Ch 3) | UTF-8 | LF | INS "));
+    }
+
+    #[test]
+    fn test_format_message() {
+        let info = StatusInfo {
+            message: "Saved 메모.md",
+            ..sample()
+        };
+        let s = StatusBar::format(80, &info);
+        assert_eq!(s.width_cjk(), 80);
+        assert!(s.starts_with(" Saved 메모.md "));
+        assert!(s.ends_with("LF | INS "));
+    }
+
+    #[test]
+    fn test_format_hex() {
+        let info = StatusInfo {
+            hex_offset: Some((0x1f, 0x200)),
+            ..sample()
+        };
+        let s = StatusBar::format(80, &info);
+        assert_eq!(s.width_cjk(), 80);
+        assert!(s.ends_with(" Offset 0x1F/0x200 | HEX "));
+    }
+}
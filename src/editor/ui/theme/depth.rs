@@ -0,0 +1,150 @@
+use crate::config::ColorDepth;
+use crossterm::style::Color;
+use std::env;
+
+/// 기본 16색의 RGB 값 (xterm 기준). 순서는 ANSI 번호 순서.
+const ANSI16: [(Color, (u8, u8, u8)); 16] = [
+    (Color::Black, (0, 0, 0)),
+    (Color::DarkRed, (205, 0, 0)),
+    (Color::DarkGreen, (0, 205, 0)),
+    (Color::DarkYellow, (205, 205, 0)),
+    (Color::DarkBlue, (0, 0, 238)),
+    (Color::DarkMagenta, (205, 0, 205)),
+    (Color::DarkCyan, (0, 205, 205)),
+    (Color::Grey, (229, 229, 229)),
+    (Color::DarkGrey, (127, 127, 127)),
+    (Color::Red, (255, 0, 0)),
+    (Color::Green, (0, 255, 0)),
+    (Color::Yellow, (255, 255, 0)),
+    (Color::Blue, (92, 92, 255)),
+    (Color::Magenta, (255, 0, 255)),
+    (Color::Cyan, (0, 255, 255)),
+    (Color::White, (255, 255, 255)),
+];
+
+/// 256색 팔레트의 6x6x6 색상 큐브 각 축의 값
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+/**
+ * 환경 변수로 터미널이 표시할 수 있는 색 수를 추측한다.
+ */
+pub fn detect() -> ColorDepth {
+    let colorterm = env::var("COLORTERM").unwrap_or_default();
+    let term = env::var("TERM").unwrap_or_default();
+    detect_from(&colorterm, &term)
+}
+
+fn detect_from(colorterm: &str, term: &str) -> ColorDepth {
+    if colorterm == "truecolor" || colorterm == "24bit" {
+        ColorDepth::TrueColor
+    } else if term.contains("256color") {
+        ColorDepth::Ansi256
+    } else {
+        ColorDepth::Ansi16
+    }
+}
+
+/**
+ * 터미널이 표시할 수 없는 색을 가장 가까운 색으로 바꾼다.
+ */
+pub fn degrade(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb { r, g, b }, ColorDepth::Ansi256) => Color::AnsiValue(nearest_256(r, g, b)),
+        (Color::Rgb { r, g, b }, ColorDepth::Ansi16) => nearest_16(r, g, b),
+        (Color::AnsiValue(n), ColorDepth::Ansi16) => {
+            let (r, g, b) = ansi_to_rgb(n);
+            nearest_16(r, g, b)
+        }
+        _ => color,
+    }
+}
+
+fn nearest_16(r: u8, g: u8, b: u8) -> Color {
+    ANSI16
+        .iter()
+        .min_by_key(|(_, rgb)| distance((r, g, b), *rgb))
+        .map(|(color, _)| *color)
+        .unwrap_or(Color::Reset)
+}
+
+/**
+ * 색상 큐브와 회색 단계 중 더 가까운 쪽을 고른다.
+ */
+fn nearest_256(r: u8, g: u8, b: u8) -> u8 {
+    let level = |v: u8| {
+        (0..CUBE_LEVELS.len())
+            .min_by_key(|&i| (CUBE_LEVELS[i] as i32 - v as i32).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (level(r), level(g), level(b));
+    let cube = (16 + 36 * ri + 6 * gi + bi) as u8;
+
+    let avg = (r as u32 + g as u32 + b as u32) / 3;
+    let gray_step = ((avg.saturating_sub(8) + 5) / 10).min(23) as u8;
+    let gray = 232 + gray_step;
+
+    if distance((r, g, b), ansi_to_rgb(gray)) < distance((r, g, b), ansi_to_rgb(cube)) {
+        gray
+    } else {
+        cube
+    }
+}
+
+fn ansi_to_rgb(n: u8) -> (u8, u8, u8) {
+    match n {
+        0..=15 => ANSI16[n as usize].1,
+        16..=231 => {
+            let i = (n - 16) as usize;
+            (
+                CUBE_LEVELS[i / 36],
+                CUBE_LEVELS[(i / 6) % 6],
+                CUBE_LEVELS[i % 6],
+            )
+        }
+        _ => {
+            let v = 8 + 10 * (n - 232);
+            (v, v, v)
+        }
+    }
+}
+
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (x as i32 - y as i32).pow(2) as u32;
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        assert_eq!(detect_from("truecolor", "xterm"), ColorDepth::TrueColor);
+        assert_eq!(detect_from("", "xterm-256color"), ColorDepth::Ansi256);
+        assert_eq!(detect_from("", "linux"), ColorDepth::Ansi16);
+    }
+
+    #[test]
+    fn test_degrade() {
+        let navy = Color::Rgb { r: 0, g: 0, b: 170 };
+        assert_eq!(degrade(navy, ColorDepth::TrueColor), navy);
+        assert_eq!(degrade(navy, ColorDepth::Ansi256), Color::AnsiValue(19));
+        assert_eq!(degrade(navy, ColorDepth::Ansi16), Color::DarkBlue);
+
+        let gray = Color::Rgb {
+            r: 100,
+            g: 100,
+            b: 100,
+        };
+        assert_eq!(degrade(gray, ColorDepth::Ansi256), Color::AnsiValue(241));
+        assert_eq!(
+            degrade(Color::AnsiValue(196), ColorDepth::Ansi16),
+            Color::Red
+        );
+        assert_eq!(
+            degrade(Color::DarkCyan, ColorDepth::Ansi16),
+            Color::DarkCyan
+        );
+    }
+}
@@ -0,0 +1,346 @@
+mod depth;
+
+pub use depth::detect as detect_color_depth;
+
+use crate::config::{ColorDepth, ColorSpec};
+use crate::consts::ui;
+use crossterm::style::Color;
+use std::collections::BTreeMap;
+
+/**
+ * 글자색과 배경색
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct ColorPair {
+    pub fg: Color,
+    pub bg: Color,
+}
+
+impl ColorPair {
+    pub const fn new(fg: Color, bg: Color) -> ColorPair {
+        ColorPair { fg, bg }
+    }
+}
+
+/**
+ * 마크다운 문법 요소의 종류. 줄 단위로 구분한다.
+ */
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum TokenKind {
+    Text,
+    /// # 제목
+    Heading,
+    /// > 인용
+    Quote,
+    /// - 목록, 1. 목록
+    List,
+    /// ``` 코드 블록 구분선, 들여쓴 코드
+    Code,
+}
+
+/**
+ * 화면 각 부분의 색. 테마를 고른 다음 설정 파일의 [colors] 로 일부를 바꿀 수 있다.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Theme {
+    pub name: &'static str,
+    /// 본문
+    pub text: ColorPair,
+    /// 선택한 글자, 목록에서 고른 항목
+    pub selection: ColorPair,
+    pub menu: ColorPair,
+    pub menu_selected: ColorPair,
+    pub menu_disabled: ColorPair,
+    pub dialog: ColorPair,
+    /// 메시지 상자의 심각도 아이콘
+    pub info: ColorPair,
+    pub warning: ColorPair,
+    pub error: ColorPair,
+    pub status: ColorPair,
+    pub gutter: ColorPair,
+    pub heading: ColorPair,
+    pub quote: ColorPair,
+    pub list: ColorPair,
+    pub code: ColorPair,
+}
+
+/// 기본 제공 테마 이름. Options > Display 에 이 순서대로 나온다.
+pub const THEME_NAMES: &[&str] = &["default", "classic", "monochrome"];
+
+impl Default for Theme {
+    /**
+     * 터미널 기본 배경에 consts::ui 의 색을 쓰는 테마
+     */
+    fn default() -> Theme {
+        let text = ColorPair::new(Color::Reset, Color::Reset);
+        Theme {
+            name: "default",
+            text,
+            selection: ColorPair::new(Color::Black, Color::Grey),
+            menu: ColorPair::new(ui::MENU_COLOR, ui::MENU_BGCOLOR),
+            menu_selected: ColorPair::new(ui::MENU_COLOR, ui::MENU_BGCOLOR_SELECTED),
+            menu_disabled: ColorPair::new(ui::MENU_COLOR_DISABLED, ui::MENU_BGCOLOR),
+            dialog: ColorPair::new(ui::DLG_BGCOLOR, ui::DLG_COLOR),
+            info: ColorPair::new(Color::White, Color::DarkBlue),
+            warning: ColorPair::new(Color::Black, Color::DarkYellow),
+            error: ColorPair::new(Color::White, Color::DarkRed),
+            status: ColorPair::new(ui::STATUS_COLOR, ui::STATUS_BGCOLOR),
+            gutter: ColorPair::new(ui::GUTTER_COLOR, ui::GUTTER_BGCOLOR),
+            heading: ColorPair::new(Color::Yellow, Color::Reset),
+            quote: ColorPair::new(Color::DarkGreen, Color::Reset),
+            list: ColorPair::new(Color::Cyan, Color::Reset),
+            code: ColorPair::new(Color::Magenta, Color::Reset),
+        }
+    }
+}
+
+impl Theme {
+    /**
+     * 기본 제공 테마. 없는 이름이면 None
+     */
+    pub fn named(name: &str) -> Option<Theme> {
+        match name {
+            "default" => Some(Theme::default()),
+            "classic" => Some(Theme::classic()),
+            "monochrome" => Some(Theme::monochrome()),
+            _ => None,
+        }
+    }
+
+    /**
+     * MS-DOS EDIT 처럼 파란 바탕에 회색 글자
+     */
+    fn classic() -> Theme {
+        // EDIT 가 쓰던 16색 팔레트 그대로
+        let blue = Color::DarkBlue;
+        let gray = Color::Grey;
+        let text = ColorPair::new(gray, blue);
+        Theme {
+            name: "classic",
+            text,
+            selection: ColorPair::new(blue, gray),
+            menu: ColorPair::new(Color::Black, gray),
+            menu_selected: ColorPair::new(gray, Color::Black),
+            menu_disabled: ColorPair::new(Color::DarkGrey, gray),
+            dialog: ColorPair::new(Color::Black, gray),
+            info: ColorPair::new(Color::White, blue),
+            warning: ColorPair::new(Color::Black, Color::DarkYellow),
+            error: ColorPair::new(Color::White, Color::DarkRed),
+            status: ColorPair::new(Color::White, Color::DarkCyan),
+            gutter: ColorPair::new(Color::Blue, blue),
+            heading: ColorPair::new(Color::White, blue),
+            quote: ColorPair::new(Color::Cyan, blue),
+            list: ColorPair::new(Color::Yellow, blue),
+            code: ColorPair::new(Color::Green, blue),
+        }
+    }
+
+    /**
+     * 검정, 흰색, 회색만 쓰는 테마
+     */
+    fn monochrome() -> Theme {
+        let text = ColorPair::new(Color::Reset, Color::Reset);
+        let inverse = ColorPair::new(Color::Black, Color::White);
+        Theme {
+            name: "monochrome",
+            text,
+            selection: inverse,
+            menu: inverse,
+            menu_selected: ColorPair::new(Color::White, Color::Black),
+            menu_disabled: ColorPair::new(Color::DarkGrey, Color::White),
+            dialog: inverse,
+            info: ColorPair::new(Color::White, Color::Black),
+            warning: ColorPair::new(Color::White, Color::Black),
+            error: ColorPair::new(Color::White, Color::Black),
+            status: inverse,
+            gutter: ColorPair::new(Color::DarkGrey, Color::Reset),
+            heading: ColorPair::new(Color::White, Color::Reset),
+            quote: ColorPair::new(Color::Grey, Color::Reset),
+            list: text,
+            code: ColorPair::new(Color::Grey, Color::Reset),
+        }
+    }
+
+    /**
+    이름으로 고른 테마에 설정 파일의 [colors] 를 덮어쓰고, 터미널이 표시할 수 있는 색으로 바꾼다.
+
+    # Arguments
+    * `name` - 테마 이름. 없는 이름이면 기본 테마를 쓴다.
+    * `colors` - 설정 파일의 [colors]
+    * `depth` - 터미널이 표시할 수 있는 색 수
+
+    # Return
+    * (테마, 없는 테마/역할 이름 등 사용자에게 알릴 문제 목록)
+    */
+    pub fn build(
+        name: &str,
+        colors: &BTreeMap<String, ColorSpec>,
+        depth: ColorDepth,
+    ) -> (Theme, Vec<String>) {
+        let mut problems = Vec::new();
+        let mut theme = Theme::named(name).unwrap_or_else(|| {
+            problems.push(format!(
+                "display.theme: unknown theme '{}' (expected one of {})",
+                name,
+                THEME_NAMES.join(", ")
+            ));
+            Theme::default()
+        });
+
+        for (role, spec) in colors {
+            match theme.role_mut(role) {
+                Some(pair) => {
+                    pair.fg = spec.fg.unwrap_or(pair.fg);
+                    pair.bg = spec.bg.unwrap_or(pair.bg);
+                }
+                None => problems.push(format!(
+                    "colors: unknown color role '{}' (expected one of {})",
+                    role,
+                    ROLES.join(", ")
+                )),
+            }
+        }
+
+        for role in ROLES {
+            if let Some(pair) = theme.role_mut(role) {
+                pair.fg = depth::degrade(pair.fg, depth);
+                pair.bg = depth::degrade(pair.bg, depth);
+            }
+        }
+
+        (theme, problems)
+    }
+
+    /**
+     * 문법 요소를 그릴 색
+     */
+    pub fn token(&self, kind: TokenKind) -> ColorPair {
+        match kind {
+            TokenKind::Text => self.text,
+            TokenKind::Heading => self.heading,
+            TokenKind::Quote => self.quote,
+            TokenKind::List => self.list,
+            TokenKind::Code => self.code,
+        }
+    }
+
+    fn role_mut(&mut self, role: &str) -> Option<&mut ColorPair> {
+        match role {
+            "text" => Some(&mut self.text),
+            "selection" => Some(&mut self.selection),
+            "menu" => Some(&mut self.menu),
+            "menu_selected" => Some(&mut self.menu_selected),
+            "menu_disabled" => Some(&mut self.menu_disabled),
+            "dialog" => Some(&mut self.dialog),
+            "info" => Some(&mut self.info),
+            "warning" => Some(&mut self.warning),
+            "error" => Some(&mut self.error),
+            "status" => Some(&mut self.status),
+            "gutter" => Some(&mut self.gutter),
+            "heading" => Some(&mut self.heading),
+            "quote" => Some(&mut self.quote),
+            "list" => Some(&mut self.list),
+            "code" => Some(&mut self.code),
+            _ => None,
+        }
+    }
+}
+
+const ROLES: &[&str] = &[
+    "text",
+    "selection",
+    "menu",
+    "menu_selected",
+    "menu_disabled",
+    "dialog",
+    "info",
+    "warning",
+    "error",
+    "status",
+    "gutter",
+    "heading",
+    "quote",
+    "list",
+    "code",
+];
+
+/**
+ * 마크다운 한 줄의 종류. 여러 줄에 걸친 코드 블록은 구분선만 코드로 표시한다.
+ */
+pub fn markdown_line_kind(line: &str) -> TokenKind {
+    let trimmed = line.trim_start();
+    let indent = line.len() - trimmed.len();
+
+    if line.starts_with('\t') || indent >= 4 || trimmed.starts_with("```") {
+        TokenKind::Code
+    } else if trimmed.starts_with('#') {
+        TokenKind::Heading
+    } else if trimmed.starts_with('>') {
+        TokenKind::Quote
+    } else if is_list_item(trimmed) {
+        TokenKind::List
+    } else {
+        TokenKind::Text
+    }
+}
+
+fn is_list_item(s: &str) -> bool {
+    if s.starts_with("- ") || s.starts_with("* ") || s.starts_with("+ ") {
+        return true;
+    }
+
+    let digits = s.chars().take_while(|c| c.is_ascii_digit()).count();
+    digits > 0 && (s[digits..].starts_with(". ") || s[digits..].starts_with(") "))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_build() {
+        let mut colors = BTreeMap::new();
+        colors.insert(
+            String::from("status"),
+            ColorSpec {
+                fg: Some(Color::White),
+                bg: None,
+            },
+        );
+        colors.insert(String::from("editor"), ColorSpec { fg: None, bg: None });
+
+        let (theme, problems) = Theme::build("default", &colors, ColorDepth::TrueColor);
+        assert_eq!(
+            theme.status,
+            ColorPair::new(Color::White, ui::STATUS_BGCOLOR)
+        );
+        assert_eq!(theme.menu, Theme::default().menu);
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].contains("'editor'"));
+
+        let (theme, problems) = Theme::build("classic", &BTreeMap::new(), ColorDepth::Ansi16);
+        assert!(problems.is_empty());
+        assert_eq!(theme.text, ColorPair::new(Color::Grey, Color::DarkBlue));
+
+        let (theme, problems) = Theme::build("solarized", &BTreeMap::new(), ColorDepth::Ansi16);
+        assert_eq!(theme.name, "default");
+        assert!(problems[0].contains("unknown theme 'solarized'"));
+
+        for name in THEME_NAMES {
+            assert_eq!(Theme::named(name).map(|theme| theme.name), Some(*name));
+        }
+    }
+
+    #[test]
+    fn test_markdown_line_kind() {
+        assert_eq!(markdown_line_kind("# 제목"), TokenKind::Heading);
+        assert_eq!(markdown_line_kind("> 인용"), TokenKind::Quote);
+        assert_eq!(markdown_line_kind("- 항목"), TokenKind::List);
+        assert_eq!(markdown_line_kind("12. 항목"), TokenKind::List);
+        assert_eq!(markdown_line_kind("```rust"), TokenKind::Code);
+        assert_eq!(markdown_line_kind("    let x = 1;"), TokenKind::Code);
+        assert_eq!(markdown_line_kind("-1 은 숫자"), TokenKind::Text);
+        assert_eq!(markdown_line_kind("2024년"), TokenKind::Text);
+        assert_eq!(markdown_line_kind(""), TokenKind::Text);
+    }
+}
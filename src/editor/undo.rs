@@ -0,0 +1,350 @@
+use super::cursor::Cursor;
+use super::line_buffer::LineBuffer;
+
+/// 되돌릴 수 있는 편집 횟수. 넘으면 가장 오래된 것부터 버린다.
+const UNDO_LIMIT: usize = 100;
+
+/**
+되돌릴 편집 하나. 문서 전체가 아니라 바뀐 줄만 가진다.
+적용하면(`apply`) 그 자리의 줄을 바꿔 넣고, 다시 적용하면 원래대로 돌아가는 반대 편집을 돌려준다.
+*/
+struct Change {
+    /// 바뀐 첫 줄
+    line: usize,
+    /// 적용할 때 `line` 부터 넣을 줄
+    lines: Vec<LineBuffer>,
+    /// 적용할 때 지금 문서에서 `line` 부터 뺄 줄 수
+    count: usize,
+    final_newline: bool,
+    /// 적용한 뒤의 커서 위치
+    cursor: Cursor,
+}
+
+impl Change {
+    fn apply(
+        self,
+        contents: &mut Vec<LineBuffer>,
+        final_newline: &mut bool,
+        cursor: Cursor,
+    ) -> (Change, Cursor) {
+        let end = (self.line + self.count).min(contents.len());
+        let count = self.lines.len();
+        let removed = contents.splice(self.line..end, self.lines).collect();
+        let inverse = Change {
+            line: self.line,
+            lines: removed,
+            count,
+            final_newline: std::mem::replace(final_newline, self.final_newline),
+            cursor,
+        };
+        (inverse, self.cursor)
+    }
+}
+
+/**
+문서 하나의 되돌리기(Ctrl+Z)/다시 실행(Ctrl+Y) 기록.
+편집하기 전에 `begin` 으로 바뀔 줄을 복사해 두고, 편집한 뒤에 `end` 로 기록에 넣는다.
+같은 줄에 이어서 입력한 글자는 한 번에 되돌리고, 이때는 줄을 다시 복사하지 않는다.
+*/
+pub struct History {
+    undo: Vec<Change>,
+    redo: Vec<Change>,
+    /// begin 으로 시작한 편집. 앞의 입력과 합치면 None
+    pending: Option<Change>,
+    /// 마지막 편집이 글자 입력이었으면 그 줄
+    typing_line: Option<usize>,
+    pending_typing: Option<usize>,
+    /// 저장한 상태에서 undo 에 쌓인 편집 수. 저장한 상태로 돌아갈 수 없으면 None
+    saved: Option<usize>,
+}
+
+impl History {
+    /**
+     * 지금 상태를 저장한 상태로 보는 빈 기록 (파일을 열었거나 새 문서)
+     */
+    pub fn new() -> History {
+        History {
+            undo: Vec::new(),
+            redo: Vec::new(),
+            pending: None,
+            typing_line: None,
+            pending_typing: None,
+            saved: Some(0),
+        }
+    }
+
+    /**
+    편집하기 전에 부른다.
+
+    # Arguments
+    * `contents` - 편집 전 문서 내용
+    * `line` - 바뀔 첫 줄
+    * `count` - 바뀌거나 지워질 줄 수. 줄을 덧붙이기만 하면 0
+    * `final_newline` - 편집 전 마지막 줄 뒤의 줄바꿈 여부
+    * `cursor` - 편집 전 커서. 되돌리면 여기로 간다.
+    * `typing` - 글자 입력인지 여부. 앞의 입력과 같은 줄이면 기록을 합친다.
+    */
+    pub fn begin(
+        &mut self,
+        contents: &[LineBuffer],
+        line: usize,
+        count: usize,
+        final_newline: bool,
+        cursor: Cursor,
+        typing: bool,
+    ) {
+        self.pending_typing = if typing { Some(line) } else { None };
+        // 저장한 바로 그 상태에 합치면 저장한 상태로 되돌아갈 수 없으므로 새로 쌓는다.
+        let merge = typing
+            && self.typing_line == Some(line)
+            && !self.undo.is_empty()
+            && self.saved != Some(self.undo.len());
+        self.pending = if merge {
+            None
+        } else {
+            let end = (line + count).min(contents.len());
+            Some(Change {
+                line,
+                lines: contents[line.min(end)..end].to_vec(),
+                count: 0,
+                final_newline,
+                cursor,
+            })
+        };
+    }
+
+    /**
+    편집한 뒤에 부른다.
+
+    # Arguments
+    * `count` - 편집 후 `begin` 의 `line` 부터 바뀐 줄 수
+    */
+    pub fn end(&mut self, count: usize) {
+        if let Some(mut change) = self.pending.take() {
+            change.count = count;
+            self.undo.push(change);
+            if self.undo.len() > UNDO_LIMIT {
+                self.undo.remove(0);
+                self.saved = match self.saved {
+                    Some(saved) if saved > 0 => Some(saved - 1),
+                    _ => None,
+                };
+            }
+        }
+        // 저장한 상태가 다시 실행할 쪽에 있었으면 더는 갈 수 없다.
+        if matches!(self.saved, Some(saved) if saved > self.undo.len())
+            || (!self.redo.is_empty() && self.saved == Some(self.undo.len()))
+        {
+            self.saved = None;
+        }
+        self.redo.clear();
+        self.typing_line = self.pending_typing.take();
+    }
+
+    /**
+    마지막 편집을 되돌린다.
+
+    # Return
+    * 되돌린 뒤의 커서 위치. 되돌릴 편집이 없으면 None
+    */
+    pub fn undo(
+        &mut self,
+        contents: &mut Vec<LineBuffer>,
+        final_newline: &mut bool,
+        cursor: Cursor,
+    ) -> Option<Cursor> {
+        let change = self.undo.pop()?;
+        let (inverse, cursor) = change.apply(contents, final_newline, cursor);
+        self.redo.push(inverse);
+        self.typing_line = None;
+        Some(cursor)
+    }
+
+    /**
+     * 되돌린 편집을 다시 한다. 돌려주는 값은 undo 와 같다.
+     */
+    pub fn redo(
+        &mut self,
+        contents: &mut Vec<LineBuffer>,
+        final_newline: &mut bool,
+        cursor: Cursor,
+    ) -> Option<Cursor> {
+        let change = self.redo.pop()?;
+        let (inverse, cursor) = change.apply(contents, final_newline, cursor);
+        self.undo.push(inverse);
+        self.typing_line = None;
+        Some(cursor)
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /**
+     * 파일에 저장했을 때 부른다.
+     */
+    pub fn mark_saved(&mut self) {
+        self.saved = Some(self.undo.len());
+        self.typing_line = None;
+    }
+
+    /**
+     * 기록에 없는 변경(저장할 인코딩 등)으로 저장한 상태와 달라졌을 때 부른다.
+     */
+    pub fn forget_saved(&mut self) {
+        self.saved = None;
+    }
+
+    /**
+     * 지금 상태가 마지막으로 저장한 상태인지 여부
+     */
+    pub fn is_saved(&self) -> bool {
+        self.saved == Some(self.undo.len())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn lines(text: &[&str]) -> Vec<LineBuffer> {
+        text.iter().map(|line| LineBuffer::from(line)).collect()
+    }
+
+    fn text(contents: &[LineBuffer]) -> Vec<&str> {
+        contents
+            .iter()
+            .map(|line| line.get_buffer().as_str())
+            .collect()
+    }
+
+    /**
+     * 문서와 같은 순서로 편집한다.
+     */
+    struct Doc {
+        contents: Vec<LineBuffer>,
+        final_newline: bool,
+        history: History,
+    }
+
+    impl Doc {
+        fn new(text: &[&str]) -> Doc {
+            Doc {
+                contents: lines(text),
+                final_newline: true,
+                history: History::new(),
+            }
+        }
+
+        fn type_at(&mut self, y: usize, line: &str) {
+            let cursor = Cursor::new();
+            self.history
+                .begin(&self.contents, y, 1, self.final_newline, cursor, true);
+            self.contents[y] = LineBuffer::from(line);
+            self.history.end(1);
+        }
+
+        fn push(&mut self, line: &str) {
+            let y = self.contents.len();
+            let cursor = Cursor::new();
+            self.history
+                .begin(&self.contents, y, 0, self.final_newline, cursor, false);
+            self.contents.push(LineBuffer::from(line));
+            self.history.end(1);
+        }
+
+        fn undo(&mut self) -> bool {
+            self.history
+                .undo(&mut self.contents, &mut self.final_newline, Cursor::new())
+                .is_some()
+        }
+
+        fn redo(&mut self) -> bool {
+            self.history
+                .redo(&mut self.contents, &mut self.final_newline, Cursor::new())
+                .is_some()
+        }
+    }
+
+    #[test]
+    fn test_typing_is_merged() {
+        let mut doc = Doc::new(&[""]);
+        doc.type_at(0, "a");
+        doc.type_at(0, "ab");
+        doc.push("");
+        doc.type_at(1, "c");
+
+        assert!(doc.undo());
+        assert_eq!(text(&doc.contents), ["ab", ""]);
+        assert!(doc.undo());
+        assert_eq!(text(&doc.contents), ["ab"]);
+        assert!(doc.undo());
+        assert_eq!(text(&doc.contents), [""]);
+        assert!(!doc.history.can_undo());
+        assert!(!doc.undo());
+
+        assert!(doc.redo());
+        assert!(doc.redo());
+        assert_eq!(text(&doc.contents), ["ab", ""]);
+        assert!(doc.history.can_redo());
+    }
+
+    #[test]
+    fn test_edit_clears_redo() {
+        let mut doc = Doc::new(&[""]);
+        doc.type_at(0, "a");
+        doc.undo();
+        assert!(doc.history.can_redo());
+
+        doc.type_at(0, "b");
+        assert!(!doc.history.can_redo());
+        doc.undo();
+        assert_eq!(text(&doc.contents), [""]);
+    }
+
+    #[test]
+    fn test_saved() {
+        let mut doc = Doc::new(&[""]);
+        assert!(doc.history.is_saved());
+        doc.type_at(0, "a");
+        doc.history.mark_saved();
+
+        // 저장한 뒤의 입력은 앞의 입력과 합치지 않으므로 저장한 상태로 되돌아간다.
+        doc.type_at(0, "ab");
+        assert!(!doc.history.is_saved());
+        doc.undo();
+        assert_eq!(text(&doc.contents), ["a"]);
+        assert!(doc.history.is_saved());
+        doc.undo();
+        assert!(!doc.history.is_saved());
+        doc.redo();
+        assert!(doc.history.is_saved());
+
+        // 저장한 상태를 되돌린 뒤에 다른 편집을 하면 저장한 상태로는 갈 수 없다.
+        doc.undo();
+        doc.type_at(0, "x");
+        assert!(!doc.history.is_saved());
+        doc.undo();
+        assert!(!doc.history.is_saved());
+    }
+
+    #[test]
+    fn test_limit() {
+        let mut doc = Doc::new(&[""]);
+        for i in 0..UNDO_LIMIT + 10 {
+            doc.push(&i.to_string());
+        }
+        let mut count = 0;
+        while doc.undo() {
+            count += 1;
+        }
+        assert_eq!(count, UNDO_LIMIT);
+        assert_eq!(doc.contents.len(), 11);
+        // 저장한 상태(처음)는 이미 버렸다.
+        assert!(!doc.history.is_saved());
+    }
+}
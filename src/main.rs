@@ -3,6 +3,7 @@
 /**
  * @author Bohun Kim
  */
+mod config;
 mod editor;
 mod consts {
     pub mod ui;
@@ -11,12 +12,14 @@ mod macros;
 
 use editor::Editor;
 
-use crossterm::terminal;
 use crossterm::terminal::size;
-use log::{info, LevelFilter};
+use log::{error, info, LevelFilter};
+use std::backtrace::Backtrace;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::Path;
 
-fn setup_log() {
-    match simple_logging::log_to_file("dev.log", LevelFilter::Info) {
+fn setup_log(path: &Path) {
+    match simple_logging::log_to_file(path, LevelFilter::Info) {
         Ok(_) => {}
         Err(e) => {
             println!("Failed to start log: {:?}", e);
@@ -25,18 +28,50 @@ fn setup_log() {
     }
 }
 
+/**
+ * main 을 어떻게 빠져나가든 (에러 리턴, 패닉) 터미널을 되돌린다.
+ */
 struct CleanUp;
 
 impl Drop for CleanUp {
     fn drop(&mut self) {
-        check_result!(terminal::disable_raw_mode(), "Unable to disable raw mode");
+        editor::restore_terminal();
     }
 }
 
+/**
+ * 패닉이 나면 먼저 터미널을 되돌린 다음 메시지를 보여주고, 메시지와 backtrace 를 로그에 남긴다.
+ */
+fn install_panic_hook() {
+    panic::set_hook(Box::new(|info| {
+        editor::restore_terminal();
+        error!("panic: {}\n{}", info, Backtrace::force_capture());
+        eprintln!("mdedit: {}", info);
+    }));
+}
+
 fn main() -> Result<(), std::io::Error> {
-    setup_log();
+    let (config, problems) = config::load();
+    setup_log(&config.log_file);
+    install_panic_hook();
+    let _clean_up = CleanUp;
+
     let (cols, rows) = size()?;
     info!("cols = {}, rows = {}", cols, rows);
-    let mut ed = Editor::new();
-    ed.run()
+    let mut ed = Editor::new(config, problems);
+    match panic::catch_unwind(AssertUnwindSafe(|| ed.run())) {
+        Ok(result) => result,
+        Err(_) => {
+            // 패닉 메시지는 panic hook 이 이미 남겼다. 저장하지 않은 내용만 살린다.
+            for result in ed.emergency_save() {
+                match result {
+                    Ok(path) => {
+                        eprintln!("mdedit: unsaved changes were written to {}", path.display())
+                    }
+                    Err(e) => eprintln!("mdedit: {}", e),
+                }
+            }
+            std::process::exit(101);
+        }
+    }
 }